@@ -0,0 +1,20 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use waffle::{BumpType, Package, ValidatedPackage};
+
+// Parses arbitrary versions through TryFrom<Package>. Any version that parses must display as the original string
+// and bumping it must never panic.
+fuzz_target!(|version: &str| {
+  let package = Package { name: None, version: version.to_owned() };
+
+  if let Ok(validated_version) = ValidatedPackage::try_from(package) {
+    assert_eq!(validated_version.to_string(), version);
+
+    for bump_type in BumpType::all() {
+      if let Ok(next_version) = validated_version.bump_version(bump_type) {
+        assert!(next_version.cmp_precedence(&validated_version).is_gt());
+      }
+    }
+  }
+});
@@ -1,38 +1,126 @@
+use std::format as s;
+use std::fmt;
+
 use crate::error::{WaffleError, ResultW};
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum BumpType {
   Major,
   Minor,
-  Patch
+  Patch,
+  /// Bump major and start a pre-release on the channel. 1.2.3 -> 2.0.0-rc.1
+  PreMajor(PreReleaseChannel),
+  /// Bump minor and start a pre-release on the channel. 1.2.3 -> 1.3.0-rc.1
+  PreMinor(PreReleaseChannel),
+  /// Bump patch and start a pre-release on the channel. 1.2.3 -> 1.2.4-rc.1
+  PrePatch(PreReleaseChannel),
+  /// Increment the pre-release on the channel, switching channels if necessary. 1.2.4-rc.1 -> 1.2.4-rc.2
+  /// A release version gets a patch bump first. 1.2.3 -> 1.2.4-rc.1
+  PreRelease(PreReleaseChannel),
+  /// Promote a pre-release to its final version. 2.0.0-rc.4 -> 2.0.0
+  Release,
+}
+
+
+/// Pre-release channels in increasing order of precedence.
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum PreReleaseChannel {
+  Alpha,
+  Beta,
+  Rc,
+}
+
+impl fmt::Display for PreReleaseChannel {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let channel = match self {
+      PreReleaseChannel::Alpha => "alpha",
+      PreReleaseChannel::Beta => "beta",
+      PreReleaseChannel::Rc => "rc",
+    };
+
+    write!(f, "{channel}")
+  }
+}
+
+
+impl fmt::Display for BumpType {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      BumpType::Major => write!(f, "major"),
+      BumpType::Minor => write!(f, "minor"),
+      BumpType::Patch => write!(f, "patch"),
+      BumpType::PreMajor(channel) => write!(f, "major {channel} pre-release"),
+      BumpType::PreMinor(channel) => write!(f, "minor {channel} pre-release"),
+      BumpType::PrePatch(channel) => write!(f, "patch {channel} pre-release"),
+      BumpType::PreRelease(channel) => write!(f, "{channel} pre-release"),
+      BumpType::Release => write!(f, "release"),
+    }
+  }
+}
+
+
+/// Serialized as its stable key, rather than its display phrase.
+impl serde::Serialize for BumpType {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&self.key())
+  }
 }
 
 
 impl BumpType {
 
-  pub fn get_bump_type(major: bool, minor: bool, patch: bool) -> ResultW<BumpType> {
+  /// A stable, machine readable name for the bump type, named after its CLI flags. e.g. pre-major-alpha
+  pub fn key(&self) -> String {
+    match self {
+      BumpType::Major => "major".to_owned(),
+      BumpType::Minor => "minor".to_owned(),
+      BumpType::Patch => "patch".to_owned(),
+      BumpType::PreMajor(channel) => s!("pre-major-{channel}"),
+      BumpType::PreMinor(channel) => s!("pre-minor-{channel}"),
+      BumpType::PrePatch(channel) => s!("pre-patch-{channel}"),
+      BumpType::PreRelease(channel) => s!("pre-release-{channel}"),
+      BumpType::Release => "release".to_owned(),
+    }
+  }
+
+
+  /// Every bump type, with the pre-release bump types for every channel.
+  pub fn all() -> Vec<BumpType> {
+    let channels = [PreReleaseChannel::Alpha, PreReleaseChannel::Beta, PreReleaseChannel::Rc];
+    let pre_release_bump_types =
+      channels
+        .into_iter()
+        .flat_map(|channel| [BumpType::PreMajor(channel), BumpType::PreMinor(channel), BumpType::PrePatch(channel), BumpType::PreRelease(channel)]);
+
+    [BumpType::Major, BumpType::Minor, BumpType::Patch]
+      .into_iter()
+      .chain(pre_release_bump_types)
+      .chain([BumpType::Release])
+      .collect()
+  }
+
+  pub fn get_bump_type(major: bool, minor: bool, patch: bool, pre: Option<PreReleaseChannel>, release: bool) -> ResultW<BumpType> {
       let set_flags =
         vec![major, minor, patch]
           .into_iter()
           .filter(|v| *v)
           .count();
 
-      if set_flags == 0 {
-        Err(WaffleError::NoBumpCombinations)
-      } else if set_flags > 1 {
+      if set_flags > 1 || (release && (set_flags > 0 || pre.is_some())) {
         Err(WaffleError::TooManyBumpCombinations)
+      } else if release {
+        Ok(BumpType::Release)
       } else {
-        let bump_type = {
-          if major {
-            BumpType::Major
-          } else if minor {
-            BumpType::Minor
-          } else {
-            BumpType::Patch
-          }
-        };
-
-        Ok(bump_type)
+        match pre {
+          None if set_flags == 0 => Err(WaffleError::NoBumpCombinations),
+          None if major => Ok(BumpType::Major),
+          None if minor => Ok(BumpType::Minor),
+          None => Ok(BumpType::Patch),
+          Some(channel) if major => Ok(BumpType::PreMajor(channel)),
+          Some(channel) if minor => Ok(BumpType::PreMinor(channel)),
+          Some(channel) if patch => Ok(BumpType::PrePatch(channel)),
+          Some(channel) => Ok(BumpType::PreRelease(channel)),
+        }
       }
   }
 }
@@ -42,9 +130,22 @@ mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
 
+    #[test]
+    fn bump_type_keys() {
+        let keys: Vec<String> = BumpType::all().iter().map(BumpType::key).collect();
+
+        assert_eq!(keys[..3], ["major", "minor", "patch"]);
+        assert!(keys.contains(&"pre-major-alpha".to_owned()));
+        assert!(keys.contains(&"pre-minor-beta".to_owned()));
+        assert!(keys.contains(&"pre-patch-rc".to_owned()));
+        assert!(keys.contains(&"pre-release-rc".to_owned()));
+        assert_eq!(keys.last().unwrap(), "release")
+    }
+
+
     #[test]
     fn get_bump_type_with_patch() {
-        let bump_type = BumpType::get_bump_type(false, false, true).unwrap();
+        let bump_type = BumpType::get_bump_type(false, false, true, None, false).unwrap();
         let expected_bump_type = BumpType::Patch;
 
         assert_eq!(bump_type, expected_bump_type)
@@ -53,7 +154,7 @@ mod tests {
 
     #[test]
     fn get_bump_type_with_minor() {
-        let bump_type = BumpType::get_bump_type(false, true, false).unwrap();
+        let bump_type = BumpType::get_bump_type(false, true, false, None, false).unwrap();
         let expected_bump_type = BumpType::Minor;
 
         assert_eq!(bump_type, expected_bump_type)
@@ -62,7 +163,7 @@ mod tests {
 
     #[test]
     fn get_bump_type_with_major() {
-        let bump_type = BumpType::get_bump_type(true, false, false).unwrap();
+        let bump_type = BumpType::get_bump_type(true, false, false, None, false).unwrap();
         let expected_bump_type = BumpType::Major;
 
         assert_eq!(bump_type, expected_bump_type)
@@ -73,10 +174,10 @@ mod tests {
     fn get_bump_type_with_more_than_one_type() {
         let expected_bump_type_error = Err(WaffleError::TooManyBumpCombinations);
 
-        let bump_type_major_minor = BumpType::get_bump_type(true, true, false);
-        let bump_type_minor_patch = BumpType::get_bump_type(false, true, true);
-        let bump_type_major_patch = BumpType::get_bump_type(true, false, true);
-        let bump_type_major_minor_patch = BumpType::get_bump_type(true, true, true);
+        let bump_type_major_minor = BumpType::get_bump_type(true, true, false, None, false);
+        let bump_type_minor_patch = BumpType::get_bump_type(false, true, true, None, false);
+        let bump_type_major_patch = BumpType::get_bump_type(true, false, true, None, false);
+        let bump_type_major_minor_patch = BumpType::get_bump_type(true, true, true, None, false);
 
         assert_eq!(bump_type_major_minor, expected_bump_type_error);
         assert_eq!(bump_type_minor_patch, expected_bump_type_error);
@@ -87,9 +188,70 @@ mod tests {
 
     #[test]
     fn get_bump_type_with_no_types() {
-        let bump_type = BumpType::get_bump_type(false, false, false);
+        let bump_type = BumpType::get_bump_type(false, false, false, None, false);
         let expected_bump_type_error = Err(WaffleError::NoBumpCombinations);
 
         assert_eq!(bump_type, expected_bump_type_error)
     }
+
+
+    #[test]
+    fn get_bump_type_with_pre_release_only() {
+        let bump_type = BumpType::get_bump_type(false, false, false, Some(PreReleaseChannel::Rc), false).unwrap();
+        let expected_bump_type = BumpType::PreRelease(PreReleaseChannel::Rc);
+
+        assert_eq!(bump_type, expected_bump_type)
+    }
+
+
+    #[test]
+    fn get_bump_type_with_pre_release_and_base_bump() {
+        let channel = Some(PreReleaseChannel::Alpha);
+
+        assert_eq!(BumpType::get_bump_type(true, false, false, channel, false).unwrap(), BumpType::PreMajor(PreReleaseChannel::Alpha));
+        assert_eq!(BumpType::get_bump_type(false, true, false, channel, false).unwrap(), BumpType::PreMinor(PreReleaseChannel::Alpha));
+        assert_eq!(BumpType::get_bump_type(false, false, true, channel, false).unwrap(), BumpType::PrePatch(PreReleaseChannel::Alpha))
+    }
+
+
+    #[test]
+    fn get_bump_type_with_pre_release_and_more_than_one_type() {
+        let bump_type = BumpType::get_bump_type(true, true, false, Some(PreReleaseChannel::Beta), false);
+        let expected_bump_type_error = Err(WaffleError::TooManyBumpCombinations);
+
+        assert_eq!(bump_type, expected_bump_type_error)
+    }
+
+
+    #[test]
+    fn get_bump_type_with_release() {
+        let bump_type = BumpType::get_bump_type(false, false, false, None, true).unwrap();
+        let expected_bump_type = BumpType::Release;
+
+        assert_eq!(bump_type, expected_bump_type)
+    }
+
+
+    #[test]
+    fn get_bump_type_with_release_and_other_types() {
+        let expected_bump_type_error = Err(WaffleError::TooManyBumpCombinations);
+
+        let bump_type_release_major = BumpType::get_bump_type(true, false, false, None, true);
+        let bump_type_release_pre = BumpType::get_bump_type(false, false, false, Some(PreReleaseChannel::Rc), true);
+
+        assert_eq!(bump_type_release_major, expected_bump_type_error);
+        assert_eq!(bump_type_release_pre, expected_bump_type_error)
+    }
+
+
+    #[test]
+    fn all_bump_types() {
+        let bump_types = BumpType::all();
+
+        assert_eq!(bump_types.len(), 16);
+        assert_eq!(bump_types.first(), Some(&BumpType::Major));
+        assert!(bump_types.contains(&BumpType::PreMinor(PreReleaseChannel::Beta)));
+        assert!(bump_types.contains(&BumpType::PreRelease(PreReleaseChannel::Alpha)));
+        assert_eq!(bump_types.last(), Some(&BumpType::Release))
+    }
 }
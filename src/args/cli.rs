@@ -1,4 +1,9 @@
-use clap::{Parser, Subcommand};
+use std::ffi::OsString;
+
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
+use log::LevelFilter;
+
+use super::PreReleaseChannel;
 
 /// Tasty version information
 #[derive(Parser, Debug, Clone)]
@@ -7,16 +12,96 @@ pub struct Args {
   #[command(subcommand)]
   pub commands: WaffleCommands,
 
-  /// Location of toml file. If not specified defaults to Cargo.toml in the current directory
-  #[arg(long)]
-  pub toml_file: Option<String>
+  /// Location of toml file. If not specified defaults to the nearest Cargo.toml in the current directory or its parents
+  #[arg(long, global = true, visible_alias = "manifest-path")]
+  pub toml_file: Option<String>,
+
+  /// Use the toml file of the workspace member with this package name
+  #[arg(long, global = true, value_name = "NAME", conflicts_with = "workspace_root")]
+  pub package: Option<String>,
+
+  /// Short for --package. Only available before the command, as -p after bump or next is a patch bump
+  #[arg(short = 'p', value_name = "NAME", conflicts_with_all = ["package", "workspace_root"])]
+  pub package_short: Option<String>,
+
+  /// Use the workspace root of the toml file instead of the toml file itself
+  #[arg(long, global = true)]
+  pub workspace_root: bool,
+
+  /// Template for Git tag names. Supports the {name}, {version}, {major}, {minor} and {patch} placeholders.
+  /// Overrides tag-template in [package.metadata.waffle] or [workspace.metadata.waffle]. Defaults to v{version}
+  #[arg(long, global = true)]
+  pub tag_template: Option<String>,
+
+  /// Output format. With json, the result is written to stdout and errors to stderr as JSON objects
+  #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+  pub output: OutputFormat,
+
+  /// Only write the result, hiding the toml file in use, diffs and other informational messages
+  #[arg(short, long, global = true)]
+  pub quiet: bool,
+
+  /// Verbose debug logging to stderr. Use -vv for trace logging
+  #[arg(short, long, global = true, action = ArgAction::Count, conflicts_with = "log_level")]
+  pub verbose: u8,
+
+  /// Log level for logging to stderr. Defaults to warn
+  #[arg(long, global = true, value_enum, value_name = "LEVEL")]
+  pub log_level: Option<LogLevel>,
+}
+
+impl Args {
+
+  /// The package name from either --package or -p.
+  pub fn package_name(&self) -> Option<&str> {
+    self.package.as_deref().or(self.package_short.as_deref())
+  }
+
+  /// The log level from --log-level or the number of --verbose flags.
+  pub fn log_level_filter(&self) -> LevelFilter {
+    match (self.log_level, self.verbose) {
+      (Some(log_level), _) => log_level.into(),
+      (None, 0) => LevelFilter::Warn,
+      (None, 1) => LevelFilter::Debug,
+      (None, _) => LevelFilter::Trace,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+  Text,
+  Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum LogLevel {
+  Off,
+  Error,
+  Warn,
+  Info,
+  Debug,
+  Trace,
+}
+
+impl From<LogLevel> for LevelFilter {
+  fn from(log_level: LogLevel) -> Self {
+    match log_level {
+      LogLevel::Off => LevelFilter::Off,
+      LogLevel::Error => LevelFilter::Error,
+      LogLevel::Warn => LevelFilter::Warn,
+      LogLevel::Info => LevelFilter::Info,
+      LogLevel::Debug => LevelFilter::Debug,
+      LogLevel::Trace => LevelFilter::Trace,
+    }
+  }
 }
 
 #[derive(Debug, Clone, Subcommand)]
 pub enum WaffleCommands {
     /// Get the project current version
     Get,
-    /// Bump the current project version to the next version. One of Major, Minor or Patch.
+    /// Bump the current project version to the next version. One of Major, Minor or Patch, optionally as a pre-release, or a Release of the current pre-release.
     Bump {
       /// Major
       #[arg(short = 'M')]
@@ -30,16 +115,150 @@ pub enum WaffleCommands {
       #[arg(short)]
       patch: bool,
 
+      /// Pre-release channel. On its own increments the current pre-release, otherwise starts a pre-release of the Major, Minor or Patch bump
+      #[arg(long, value_name = "CHANNEL")]
+      pre: Option<PreReleaseChannel>,
+
+      /// Promote the current pre-release to its final version
+      #[arg(long)]
+      release: bool,
+
+      /// Bump the version of every member of the workspace
+      #[arg(long)]
+      workspace: bool,
+
+      /// Infer Major, Minor or Patch from the Conventional Commits since the last version tag
+      #[arg(long, conflicts_with_all = ["major", "minor", "patch", "release", "workspace"])]
+      auto: bool,
+
+      /// Commit the files changed by the bump to Git
+      #[arg(long, conflicts_with = "workspace")]
+      commit: bool,
+
+      /// Template for the commit message. Supports the {name}, {old_version} and {new_version} placeholders.
+      /// Overrides commit-message in [package.metadata.waffle] or [workspace.metadata.waffle]. Defaults to "Bump version to {new_version}"
+      #[arg(long, requires = "commit", value_name = "TEMPLATE")]
+      commit_message: Option<String>,
+
+      /// Tag the bump commit using the tag template
+      #[arg(long, requires = "commit")]
+      tag: bool,
+
+      /// Add a CHANGELOG.md entry for the new version from the Conventional Commits since the last version tag
+      #[arg(long, conflicts_with = "workspace")]
+      changelog: bool,
+
+      /// Release an empty `[Unreleased]` section of CHANGELOG.md instead of failing
+      #[arg(long, conflicts_with_all = ["workspace", "changelog"])]
+      allow_empty_changelog: bool,
+
       /// Hide change diff
       #[arg(long)]
       no_diff: bool,
 
+      /// Show the changes the bump would make without writing any files, committing or tagging
+      #[arg(long)]
+      dry_run: bool,
+    },
+    /// Show the version each bump type would produce, without changing any files
+    Next {
+      /// Only show the Major bump
+      #[arg(short = 'M')]
+      major: bool,
+
+      /// Only show the Minor bump
+      #[arg(short)]
+      minor: bool,
+
+      /// Only show the Patch bump
+      #[arg(short)]
+      patch: bool,
+
+      /// Only show the pre-release bump on this channel, or of the Major, Minor or Patch bump
+      #[arg(long, value_name = "CHANNEL")]
+      pre: Option<PreReleaseChannel>,
+
+      /// Only show the release of the current pre-release
+      #[arg(long)]
+      release: bool,
+    },
+    /// Set the project version to an explicit version
+    Set {
+      /// The version to set. Must be a valid Semantic Version
+      version: String,
+
+      /// Allow setting a version that is lower than the current version
+      #[arg(long)]
+      allow_downgrade: bool,
+
+      /// Hide change diff
+      #[arg(long)]
+      no_diff: bool,
     },
     /// Displays command to Git tag current project version
-    Tag,
+    Tag {
+      /// Create the tag in the enclosing Git repository instead of displaying the command
+      #[arg(long)]
+      create: bool,
+
+      /// Create an annotated tag instead of a lightweight one
+      #[arg(long, requires = "create")]
+      annotated: bool,
+
+      /// Message template for annotated tags. Supports the {tag} placeholder along with those of the tag template
+      #[arg(long, requires = "annotated", default_value = "Release {version}")]
+      message: String,
+    },
 }
 
 
+/// Parses the arguments of the current process, also when run as `cargo waffle`. Exits with usage information if they
+/// are invalid.
 pub fn get_cli_args() -> Args {
-  Args::parse()
+  Args::parse_from(without_cargo_subcommand(std::env::args_os().collect()))
+}
+
+
+/// When run as `cargo waffle`, Cargo runs `cargo-waffle waffle <args>`, so the extra `waffle` is dropped.
+fn without_cargo_subcommand(mut args: Vec<OsString>) -> Vec<OsString> {
+  if args.get(1).is_some_and(|arg| arg == "waffle") {
+    args.remove(1);
+  }
+
+  args
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn args(args: &[&str]) -> Vec<OsString> {
+      args.iter().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn drops_cargo_subcommand() {
+      assert_eq!(without_cargo_subcommand(args(&["cargo-waffle", "waffle", "bump", "-p"])), args(&["cargo-waffle", "bump", "-p"]));
+      assert_eq!(without_cargo_subcommand(args(&["waffle", "bump", "-p"])), args(&["waffle", "bump", "-p"]));
+      assert_eq!(without_cargo_subcommand(args(&["waffle"])), args(&["waffle"]))
+    }
+
+    #[test]
+    fn parses_cargo_arguments() {
+      let args = Args::parse_from(without_cargo_subcommand(args(&["cargo-waffle", "waffle", "--manifest-path", "app/Cargo.toml", "-p", "app", "bump", "-p"])));
+
+      assert_eq!(args.toml_file.as_deref(), Some("app/Cargo.toml"));
+      assert_eq!(args.package_name(), Some("app"));
+      assert!(matches!(args.commands, WaffleCommands::Bump { patch: true, .. }))
+    }
+
+    #[test]
+    fn parses_package_after_command() {
+      let args = Args::parse_from(without_cargo_subcommand(args(&["cargo-waffle", "waffle", "bump", "--package", "app", "-p"])));
+
+      assert_eq!(args.package_name(), Some("app"));
+      assert!(matches!(args.commands, WaffleCommands::Bump { patch: true, .. }))
+    }
 }
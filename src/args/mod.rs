@@ -1,4 +1,5 @@
 pub mod cli;
 pub mod bump_type;
 
-pub use bump_type::BumpType;
+pub use bump_type::{BumpType, PreReleaseChannel};
+pub use cli::OutputFormat;
@@ -0,0 +1,12 @@
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+  let args = waffle::get_cli_args();
+
+  env_logger::Builder::new()
+    .filter_level(args.log_level_filter())
+    .format_timestamp(None)
+    .init();
+
+  waffle::perform_workflow(args)
+}
@@ -0,0 +1,267 @@
+use std::format as s;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::conventional::{CommitKind, ConventionalCommit};
+use crate::wtoml::ValidatedPackage;
+
+pub const CHANGELOG_FILE: &str = "CHANGELOG.md";
+
+const CHANGELOG_HEADER: &str = "# Changelog
+
+All notable changes to this project will be documented in this file.
+
+The format is based on [Keep a Changelog](https://keepachangelog.com/en/1.1.0/),
+and this project adheres to [Semantic Versioning](https://semver.org/spec/v2.0.0.html).
+";
+
+const UNRELEASED_HEADING: &str = "## [Unreleased]";
+
+
+/// Renders a Keep a Changelog release section, grouping commits into Breaking, Features and Fixes.
+pub fn render_release_section(version: &ValidatedPackage, date: &str, commits: &[ConventionalCommit]) -> String {
+  let groups = [
+    ("Breaking", CommitKind::Breaking),
+    ("Features", CommitKind::Feature),
+    ("Fixes", CommitKind::Fix),
+  ];
+
+  let mut section = s!("## [{version}] - {date}\n");
+
+  for (title, kind) in groups {
+    let entries: Vec<_> =
+      commits
+        .iter()
+        .filter(|commit| commit.kind == kind)
+        .map(|commit| s!("- {} ({})", commit.description, &commit.hash[..commit.hash.len().min(7)]))
+        .collect();
+
+    if !entries.is_empty() {
+      section.push_str(&s!("\n### {title}\n\n{}\n", entries.join("\n")))
+    }
+  }
+
+  section
+}
+
+
+/// Inserts a release section above the most recent release, keeping the header and any Unreleased section at the top.
+/// An empty changelog gets the standard Keep a Changelog header.
+pub fn insert_release_section(changelog: &str, section: &str) -> String {
+  if changelog.trim().is_empty() {
+    return s!("{CHANGELOG_HEADER}\n{section}")
+  }
+
+  let mut offset = 0;
+  for line in changelog.split_inclusive('\n') {
+    if line.starts_with("## ") && !line.starts_with(UNRELEASED_HEADING) {
+      return s!("{}{section}\n{}", &changelog[..offset], &changelog[offset..])
+    }
+
+    offset += line.len();
+  }
+
+  // No previous releases, so the section goes at the end after a blank line
+  let separator = if changelog.ends_with("\n\n") { "" } else if changelog.ends_with('\n') { "\n" } else { "\n\n" };
+  s!("{changelog}{separator}{section}")
+}
+
+
+/// The content of the Unreleased section, if there is one. The section ends at the next heading or the link references.
+pub fn unreleased_section(changelog: &str) -> Option<String> {
+  let mut lines = changelog.lines().skip_while(|line| !line.starts_with(UNRELEASED_HEADING));
+  lines.next()?;
+
+  let section: Vec<_> =
+    lines
+      .take_while(|line| !line.starts_with("## ") && !is_link_reference(line))
+      .collect();
+
+  Some(section.join("\n"))
+}
+
+
+/// Renames the Unreleased section to a release section for version and adds a fresh Unreleased section above it.
+/// If the Unreleased link reference compares against HEAD, it's moved on to the new tag and a link reference comparing
+/// the previous tag with the new tag is added for the release.
+pub fn promote_unreleased_section(changelog: &str, version: &ValidatedPackage, date: &str, tag: &str) -> String {
+  changelog
+    .split_inclusive('\n')
+    .map(|line| {
+      if let Some(rest) = line.strip_prefix(UNRELEASED_HEADING) {
+        s!("{UNRELEASED_HEADING}\n\n## [{version}] - {date}{rest}")
+      } else if let Some((base_url, previous_tag, line_ending)) = unreleased_link(line) {
+        s!("[Unreleased]: {base_url}/compare/{tag}...HEAD{line_ending}[{version}]: {base_url}/compare/{previous_tag}...{tag}{line_ending}")
+      } else {
+        line.to_owned()
+      }
+    })
+    .collect()
+}
+
+
+fn is_link_reference(line: &str) -> bool {
+  line.starts_with('[') && line.contains("]: ")
+}
+
+
+/// Splits a link reference like `[Unreleased]: https://github.com/owner/repo/compare/v1.2.3...HEAD` into the base url,
+/// the previous tag and the line ending.
+fn unreleased_link(line: &str) -> Option<(&str, &str, &str)> {
+  let content = line.trim_end_matches(['\r', '\n']);
+  let line_ending = &line[content.len()..];
+  let url = content.strip_prefix("[Unreleased]: ")?.trim();
+  let (base_url, range) = url.rsplit_once("/compare/")?;
+  let previous_tag = range.strip_suffix("...HEAD")?;
+
+  Some((base_url, previous_tag, line_ending))
+}
+
+
+/// Today's date in UTC as YYYY-MM-DD
+pub fn today() -> String {
+  let days =
+    SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|d| d.as_secs() / 86_400)
+      .unwrap_or_default();
+
+  let (year, month, day) = civil_from_days(days as i64);
+  s!("{year:04}-{month:02}-{day:02}")
+}
+
+
+/// Converts days since the Unix epoch to a (year, month, day) in the proleptic Gregorian calendar.
+/// See: https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+  let z = days + 719_468;
+  let era = z.div_euclid(146_097);
+  let day_of_era = z.rem_euclid(146_097);
+  let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+  let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+  let shifted_month = (5 * day_of_year + 2) / 153;
+  let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+  let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 } as u32;
+  let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+  (year, month, day)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn renders_grouped_release_section() {
+      let commits = [
+        ConventionalCommit::new("1111111aaa", "fix: handle empty versions"),
+        ConventionalCommit::new("2222222bbb", "chore: update dependencies"),
+        ConventionalCommit::new("3333333ccc", "feat(cli): add set command"),
+        ConventionalCommit::new("4444444ddd", "feat!: drop u16 versions"),
+      ];
+
+      let expected_section = "## [1.3.0] - 2026-10-18
+
+### Breaking
+
+- drop u16 versions (4444444)
+
+### Features
+
+- add set command (3333333)
+
+### Fixes
+
+- handle empty versions (1111111)
+";
+
+      assert_eq!(render_release_section(&ValidatedPackage::new("1.3.0"), "2026-10-18", &commits), expected_section)
+    }
+
+    #[test]
+    fn inserts_section_into_empty_changelog() {
+      let changelog = insert_release_section("", "## [1.3.0] - 2026-10-18\n");
+
+      assert_eq!(changelog, s!("{CHANGELOG_HEADER}\n## [1.3.0] - 2026-10-18\n"))
+    }
+
+    #[test]
+    fn inserts_section_above_previous_release() {
+      let changelog = "# Changelog\n\n## [Unreleased]\n\n## [1.2.3] - 2026-01-01\n\n- old\n";
+      let expected_changelog = "# Changelog\n\n## [Unreleased]\n\n## [1.3.0] - 2026-10-18\n\n- new\n\n## [1.2.3] - 2026-01-01\n\n- old\n";
+
+      assert_eq!(insert_release_section(changelog, "## [1.3.0] - 2026-10-18\n\n- new\n"), expected_changelog)
+    }
+
+    #[test]
+    fn inserts_section_after_header_without_releases() {
+      let changelog = "# Changelog\n\nSome notes";
+      let expected_changelog = "# Changelog\n\nSome notes\n\n## [1.3.0] - 2026-10-18\n";
+
+      assert_eq!(insert_release_section(changelog, "## [1.3.0] - 2026-10-18\n"), expected_changelog)
+    }
+
+    const UNRELEASED_CHANGELOG: &str = "# Changelog
+
+## [Unreleased]
+
+### Added
+
+- A set command
+
+## [1.2.3] - 2026-01-01
+
+- old
+
+[Unreleased]: https://github.com/ssanj/waffle/compare/v1.2.3...HEAD
+[1.2.3]: https://github.com/ssanj/waffle/compare/v1.2.2...v1.2.3
+";
+
+    #[test]
+    fn finds_unreleased_section() {
+      assert_eq!(unreleased_section(UNRELEASED_CHANGELOG), Some("\n### Added\n\n- A set command\n".to_owned()));
+      assert_eq!(unreleased_section("# Changelog\n\n## [Unreleased]\n\n[Unreleased]: https://example.com\n"), Some("".to_owned()));
+      assert_eq!(unreleased_section("# Changelog\n\n## [1.2.3] - 2026-01-01\n"), None)
+    }
+
+    #[test]
+    fn promotes_unreleased_section() {
+      let expected_changelog = "# Changelog
+
+## [Unreleased]
+
+## [1.3.0] - 2026-10-18
+
+### Added
+
+- A set command
+
+## [1.2.3] - 2026-01-01
+
+- old
+
+[Unreleased]: https://github.com/ssanj/waffle/compare/v1.3.0...HEAD
+[1.3.0]: https://github.com/ssanj/waffle/compare/v1.2.3...v1.3.0
+[1.2.3]: https://github.com/ssanj/waffle/compare/v1.2.2...v1.2.3
+";
+
+      assert_eq!(promote_unreleased_section(UNRELEASED_CHANGELOG, &ValidatedPackage::new("1.3.0"), "2026-10-18", "v1.3.0"), expected_changelog)
+    }
+
+    #[test]
+    fn promotes_unreleased_section_without_compare_links() {
+      let changelog = "## [Unreleased]\n\n- A set command\n\n[Unreleased]: https://example.com/changes\n";
+      let expected_changelog = "## [Unreleased]\n\n## [1.3.0] - 2026-10-18\n\n- A set command\n\n[Unreleased]: https://example.com/changes\n";
+
+      assert_eq!(promote_unreleased_section(changelog, &ValidatedPackage::new("1.3.0"), "2026-10-18", "v1.3.0"), expected_changelog)
+    }
+
+    #[test]
+    fn converts_days_to_dates() {
+      assert_eq!(civil_from_days(0), (1970, 1, 1));
+      assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+      assert_eq!(civil_from_days(20_744), (2026, 10, 18));
+      assert_eq!(civil_from_days(-1), (1969, 12, 31));
+    }
+}
@@ -0,0 +1,3 @@
+mod changelog_tools;
+
+pub use changelog_tools::{insert_release_section, promote_unreleased_section, render_release_section, today, unreleased_section, CHANGELOG_FILE};
@@ -0,0 +1,262 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::format as s;
+use log::{debug, warn};
+
+use crate::error::{FileName, ResultW, WaffleError};
+
+/// A pending change to the content of a file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileChange {
+  pub file: PathBuf,
+  pub content: String,
+  pub new_content: String,
+  /// The file doesn't exist yet and is created by this change.
+  pub is_new: bool,
+}
+
+impl FileChange {
+
+  pub fn new<P: AsRef<Path>>(file: P, content: &str, new_content: &str) -> Self {
+    Self {
+      file: file.as_ref().to_owned(),
+      content: content.to_owned(),
+      new_content: new_content.to_owned(),
+      is_new: false,
+    }
+  }
+}
+
+
+/// Pending changes to a set of files. Successive updates to the same file build on each other.
+#[derive(Debug, Default)]
+pub struct Changeset {
+  changes: Vec<FileChange>,
+}
+
+impl Changeset {
+
+  pub fn changes(&self) -> &[FileChange] {
+    &self.changes
+  }
+
+  /// Updates the content of a file with the supplied function. The function receives the pending content of the file if
+  /// it has already been updated, or the content on disk otherwise. Updates that don't alter the content are dropped.
+  pub fn update_file<P, F>(&mut self, file: P, update: F) -> ResultW<()>
+    where P: AsRef<Path>,
+          F: FnOnce(&Path, &str) -> ResultW<String>
+  {
+    self.update(file, false, update)
+  }
+
+  /// Like update_file, but a file that doesn't exist yet is treated as empty and created when the changes are written.
+  pub fn update_or_create_file<P, F>(&mut self, file: P, update: F) -> ResultW<()>
+    where P: AsRef<Path>,
+          F: FnOnce(&Path, &str) -> ResultW<String>
+  {
+    self.update(file, true, update)
+  }
+
+  fn update<P, F>(&mut self, file: P, create: bool, update: F) -> ResultW<()>
+    where P: AsRef<Path>,
+          F: FnOnce(&Path, &str) -> ResultW<String>
+  {
+    let is_new = create && !file.as_ref().exists();
+
+    let file =
+      if is_new {
+        canonicalize_new_file(file.as_ref())
+      } else {
+        file.as_ref().canonicalize()
+      }
+      .map_err(|e| WaffleError::CouldNotReadFile(FileName::new(file.as_ref()), e.to_string()))?;
+
+    match self.changes.iter_mut().find(|change| change.file == file) {
+      Some(change) => {
+        change.new_content = update(&file, &change.new_content)?;
+      },
+
+      None if is_new => {
+        let new_content = update(&file, "")?;
+        self.changes.push(FileChange { is_new, ..FileChange::new(&file, "", &new_content) })
+      },
+
+      None => {
+        let content =
+          fs::read_to_string(&file)
+            .map_err(|e| WaffleError::CouldNotReadFile(FileName::new(&file), e.to_string()))?;
+
+        let new_content = update(&file, &content)?;
+        self.changes.push(FileChange::new(&file, &content, &new_content))
+      },
+    }
+
+    self.changes.retain(|change| change.content != change.new_content);
+
+    Ok(())
+  }
+}
+
+
+/// Writes all changes or none of them.
+///
+/// New content is first written to a temporary file next to each target, and only once every temporary file has been
+/// written are they renamed over their targets. If a rename fails, the targets that were already replaced are restored
+/// to their original content.
+pub fn write_file_changes(changes: &[FileChange]) -> ResultW<()> {
+  let mut temp_files = Vec::with_capacity(changes.len());
+
+  for change in changes {
+    let temp_file = temp_file_for(&change.file);
+    debug!("Writing new content of {} to: {}", change.file.to_string_lossy(), temp_file.to_string_lossy());
+
+    if let Err(e) = fs::write(&temp_file, &change.new_content) {
+      remove_files(&temp_files);
+      let _ = fs::remove_file(&temp_file);
+      return Err(WaffleError::CouldNotWriteFile(FileName::new(&change.file), e.to_string()))
+    }
+
+    temp_files.push(temp_file);
+  }
+
+  for (index, (change, temp_file)) in changes.iter().zip(temp_files.iter()).enumerate() {
+    debug!("Replacing: {}", change.file.to_string_lossy());
+
+    if let Err(e) = fs::rename(temp_file, &change.file) {
+      warn!("Could not replace {}, restoring {} replaced file(s)", change.file.to_string_lossy(), index);
+      restore_file_changes(&changes[..index]);
+      remove_files(&temp_files[index..]);
+      return Err(WaffleError::CouldNotWriteFile(FileName::new(&change.file), e.to_string()))
+    }
+  }
+
+  Ok(())
+}
+
+
+/// The parent directory must exist, as that's where the file will be created.
+fn canonicalize_new_file(file: &Path) -> std::io::Result<PathBuf> {
+  let parent = file.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+  let file_name = file.file_name().unwrap_or_default();
+
+  Ok(parent.canonicalize()?.join(file_name))
+}
+
+
+fn temp_file_for(file: &Path) -> PathBuf {
+  let file_name =
+    file
+      .file_name()
+      .map(|name| name.to_string_lossy().to_string())
+      .unwrap_or_default();
+
+  file.with_file_name(s!(".{file_name}.waffle"))
+}
+
+
+/// Restores the files to their original content, removing any files that were created.
+pub fn restore_file_changes(changes: &[FileChange]) {
+  for change in changes {
+    if change.is_new {
+      let _ = fs::remove_file(&change.file);
+    } else {
+      let _ = fs::write(&change.file, &change.content);
+    }
+  }
+}
+
+
+fn remove_files(files: &[PathBuf]) {
+  for file in files {
+    let _ = fs::remove_file(file);
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    #[test]
+    fn writes_all_changes() {
+      let working_dir = tempdir().unwrap();
+      let first_file = working_dir.path().join("first.toml");
+      let second_file = working_dir.path().join("second.toml");
+      fs::write(&first_file, "first").unwrap();
+      fs::write(&second_file, "second").unwrap();
+
+      let changes =
+        [
+          FileChange::new(&first_file, "first", "first updated"),
+          FileChange::new(&second_file, "second", "second updated"),
+        ];
+
+      write_file_changes(&changes).unwrap();
+
+      assert_eq!(fs::read_to_string(&first_file).unwrap(), "first updated");
+      assert_eq!(fs::read_to_string(&second_file).unwrap(), "second updated");
+      assert_eq!(fs::read_dir(working_dir.path()).unwrap().count(), 2)
+    }
+
+    #[test]
+    fn combines_updates_to_the_same_file() {
+      let working_dir = tempdir().unwrap();
+      let file = working_dir.path().join("file.toml");
+      fs::write(&file, "content").unwrap();
+
+      let mut changeset = Changeset::default();
+      changeset.update_file(&file, |_, content| Ok(s!("{content} first"))).unwrap();
+      changeset.update_file(&file, |_, content| Ok(s!("{content} second"))).unwrap();
+
+      let expected_changes = [FileChange::new(file.canonicalize().unwrap(), "content", "content first second")];
+
+      assert_eq!(changeset.changes(), expected_changes)
+    }
+
+    #[test]
+    fn drops_updates_without_changes() {
+      let working_dir = tempdir().unwrap();
+      let file = working_dir.path().join("file.toml");
+      fs::write(&file, "content").unwrap();
+
+      let mut changeset = Changeset::default();
+      changeset.update_file(&file, |_, content| Ok(content.to_owned())).unwrap();
+
+      assert!(changeset.changes().is_empty())
+    }
+
+    #[test]
+    fn creates_missing_files() {
+      let working_dir = tempdir().unwrap();
+      let file = working_dir.path().join("CHANGELOG.md");
+
+      let mut changeset = Changeset::default();
+      changeset.update_or_create_file(&file, |_, content| Ok(s!("{content}created"))).unwrap();
+      write_file_changes(changeset.changes()).unwrap();
+
+      assert!(changeset.changes()[0].is_new);
+      assert_eq!(fs::read_to_string(&file).unwrap(), "created")
+    }
+
+    #[test]
+    fn writes_no_changes_on_failure() {
+      let working_dir = tempdir().unwrap();
+      let first_file = working_dir.path().join("first.toml");
+      let missing_file = working_dir.path().join("missing").join("second.toml");
+      fs::write(&first_file, "first").unwrap();
+
+      let changes =
+        [
+          FileChange::new(&first_file, "first", "first updated"),
+          FileChange::new(&missing_file, "second", "second updated"),
+        ];
+
+      let result = write_file_changes(&changes);
+
+      assert!(matches!(result, Err(WaffleError::CouldNotWriteFile(..))));
+      assert_eq!(fs::read_to_string(&first_file).unwrap(), "first");
+      assert_eq!(fs::read_dir(working_dir.path()).unwrap().count(), 1)
+    }
+}
@@ -0,0 +1,3 @@
+mod changeset_tools;
+
+pub use changeset_tools::{Changeset, restore_file_changes, write_file_changes};
@@ -0,0 +1,167 @@
+use crate::args::BumpType;
+use crate::wtoml::ValidatedPackage;
+
+/// The kind of a commit according to Conventional Commits (https://www.conventionalcommits.org)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CommitKind {
+  Other,
+  Fix,
+  Feature,
+  Breaking,
+}
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConventionalCommit {
+  pub hash: String,
+  /// The first line of the commit message
+  pub summary: String,
+  /// The description from the summary, without the type and scope
+  pub description: String,
+  pub kind: CommitKind,
+}
+
+impl ConventionalCommit {
+
+  pub fn new(hash: &str, message: &str) -> Self {
+    let summary = message.lines().next().unwrap_or_default().trim().to_owned();
+    let (kind, description) = classify(&summary, message);
+
+    Self {
+      hash: hash.to_owned(),
+      summary,
+      description,
+      kind,
+    }
+  }
+}
+
+
+/// Classifies a commit from its header of the form: type(scope)!: description
+/// A `!` before the colon or a BREAKING CHANGE footer marks a breaking change.
+fn classify(summary: &str, message: &str) -> (CommitKind, String) {
+  let has_breaking_footer =
+    message
+      .lines()
+      .skip(1)
+      .any(|line| line.starts_with("BREAKING CHANGE:") || line.starts_with("BREAKING-CHANGE:"));
+
+  let Some((prefix, description)) = summary.split_once(':') else {
+    return (CommitKind::Other, summary.to_owned())
+  };
+
+  let description = description.trim().to_owned();
+  let (prefix, is_breaking) = match prefix.strip_suffix('!') {
+    Some(prefix) => (prefix, true),
+    None => (prefix, has_breaking_footer),
+  };
+
+  let commit_type = match prefix.split_once('(') {
+    Some((commit_type, scope)) if scope.ends_with(')') => commit_type,
+    Some(_) => return (CommitKind::Other, summary.to_owned()),
+    None => prefix,
+  };
+
+  if commit_type.is_empty() || !commit_type.chars().all(|c| c.is_ascii_alphabetic()) {
+    return (CommitKind::Other, summary.to_owned())
+  }
+
+  let kind = match commit_type.to_ascii_lowercase().as_str() {
+    _ if is_breaking => CommitKind::Breaking,
+    "feat" => CommitKind::Feature,
+    "fix" => CommitKind::Fix,
+    _ => CommitKind::Other,
+  };
+
+  (kind, description)
+}
+
+
+/// Infers the bump type from the most significant commit: breaking changes bump major, features bump minor and fixes
+/// bump patch. While the major version is 0, breaking changes bump minor instead.
+/// Returns the bump type along with the commits that drove the decision, or None if there are no features, fixes or
+/// breaking changes.
+pub fn infer_bump_type<'a>(commits: &'a [ConventionalCommit], current_version: &ValidatedPackage) -> Option<(BumpType, Vec<&'a ConventionalCommit>)> {
+  let most_significant_kind =
+    commits
+      .iter()
+      .map(|commit| commit.kind)
+      .max()
+      .filter(|kind| *kind != CommitKind::Other)?;
+
+  let bump_type = match most_significant_kind {
+    CommitKind::Breaking if current_version.major == 0 => BumpType::Minor,
+    CommitKind::Breaking => BumpType::Major,
+    CommitKind::Feature => BumpType::Minor,
+    _ => BumpType::Patch,
+  };
+
+  let driving_commits =
+    commits
+      .iter()
+      .filter(|commit| commit.kind == most_significant_kind)
+      .collect();
+
+  Some((bump_type, driving_commits))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn kind(message: &str) -> CommitKind {
+      ConventionalCommit::new("abc", message).kind
+    }
+
+    #[test]
+    fn classifies_commits() {
+      assert_eq!(kind("feat: add set command"), CommitKind::Feature);
+      assert_eq!(kind("feat(cli): add set command"), CommitKind::Feature);
+      assert_eq!(kind("fix: handle empty versions"), CommitKind::Fix);
+      assert_eq!(kind("FIX: handle empty versions"), CommitKind::Fix);
+      assert_eq!(kind("feat!: drop u16 versions"), CommitKind::Breaking);
+      assert_eq!(kind("refactor(wtoml)!: rename functions"), CommitKind::Breaking);
+      assert_eq!(kind("feat: new api\n\nBREAKING CHANGE: removes the old api"), CommitKind::Breaking);
+      assert_eq!(kind("chore: update dependencies"), CommitKind::Other);
+      assert_eq!(kind("Update README"), CommitKind::Other);
+      assert_eq!(kind("Merge branch 'main': conflicts"), CommitKind::Other);
+      assert_eq!(kind("feat(cli: broken scope"), CommitKind::Other);
+    }
+
+    #[test]
+    fn extracts_description() {
+      let commit = ConventionalCommit::new("abc", "feat(cli)!: add set command\n\nmore details");
+
+      assert_eq!(commit.summary, "feat(cli)!: add set command");
+      assert_eq!(commit.description, "add set command")
+    }
+
+    #[test]
+    fn infers_bump_type_from_most_significant_commit() {
+      let fix = ConventionalCommit::new("1", "fix: a fix");
+      let feature = ConventionalCommit::new("2", "feat: a feature");
+      let breaking = ConventionalCommit::new("3", "feat!: a breaking change");
+      let chore = ConventionalCommit::new("4", "chore: a chore");
+
+      let commits = [fix.clone(), chore.clone()];
+      assert_eq!(infer_bump_type(&commits, &ValidatedPackage::new("1.2.3")), Some((BumpType::Patch, vec![&fix])));
+
+      let commits = [fix.clone(), feature.clone(), chore.clone()];
+      assert_eq!(infer_bump_type(&commits, &ValidatedPackage::new("1.2.3")), Some((BumpType::Minor, vec![&feature])));
+
+      let commits = [fix.clone(), feature.clone(), breaking.clone()];
+      assert_eq!(infer_bump_type(&commits, &ValidatedPackage::new("1.2.3")), Some((BumpType::Major, vec![&breaking])));
+
+      let commits = [chore.clone()];
+      assert_eq!(infer_bump_type(&commits, &ValidatedPackage::new("1.2.3")), None)
+    }
+
+    #[test]
+    fn breaking_change_bumps_minor_before_1_0_0() {
+      let commits = [ConventionalCommit::new("1", "fix!: a breaking fix")];
+
+      assert_eq!(infer_bump_type(&commits, &ValidatedPackage::new("0.4.2")).map(|(bump_type, _)| bump_type), Some(BumpType::Minor))
+    }
+}
@@ -0,0 +1,3 @@
+mod conventional_tools;
+
+pub use conventional_tools::{CommitKind, ConventionalCommit, infer_bump_type};
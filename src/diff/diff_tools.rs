@@ -1,19 +1,28 @@
 use std::fmt::Display;
 use ansi_term::Colour;
+use log::trace;
 use similar::{ChangeTag, TextDiff};
 
 
-pub fn show_diff(content: &str, new_content: &str) {
+/// Renders the changed lines, one per line.
+pub fn render_diff(content: &str, new_content: &str) -> String {
     let text_diff = TextDiff::from_lines(content, new_content);
-    for change in text_diff.iter_all_changes() {
-      match change.tag() {
-        ChangeTag::Delete => print_diff("-", Colour::Red, change),
-        ChangeTag::Insert => print_diff("+", Colour::Green, change),
-        ChangeTag::Equal => (),
-      }
-    }
+    let changed_lines: Vec<_> =
+      text_diff
+        .iter_all_changes()
+        .filter_map(|change| {
+          match change.tag() {
+            ChangeTag::Delete => Some(render_change("-", Colour::Red, change)),
+            ChangeTag::Insert => Some(render_change("+", Colour::Green, change)),
+            ChangeTag::Equal => None,
+          }
+        })
+        .collect();
+
+    trace!("Rendered {} changed line(s)", changed_lines.len());
+    changed_lines.join("\n")
 }
 
-fn print_diff<T: Display>(prefix: &str, colour: Colour, message: T) {
-  print!("  {}{}", colour.paint(prefix), message)
+fn render_change<T: Display>(prefix: &str, colour: Colour, message: T) -> String {
+  format!("  {}{}", colour.paint(prefix), message.to_string().trim_end_matches(['\r', '\n']))
 }
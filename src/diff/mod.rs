@@ -1,5 +1,5 @@
 mod diff_tools;
 
-pub use diff_tools::show_diff;
+pub use diff_tools::render_diff;
 
 
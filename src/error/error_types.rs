@@ -2,7 +2,8 @@ use std::path::{Path, PathBuf};
 use std::fmt;
 use std::format as s;
 
-use crate::wtoml::Package;
+use crate::wtoml::{Package, ValidatedPackage};
+use crate::args::{BumpType, PreReleaseChannel};
 
 pub type ResultW<T> = Result<T, WaffleError>;
 
@@ -43,13 +44,88 @@ impl fmt::Display for TomlContent {
 #[derive(Debug, PartialEq)]
 pub enum WaffleError {
   CouldNotReadTomlFile(FileName, String),
-  CouldNotUpdateTomlFile(FileName, TomlContent, String),
   CouldParseTomlFile(FileName, String),
   CouldConvertTomlContentToDocument(FileName, TomlContent, String),
   TooManyBumpCombinations,
   NoBumpCombinations,
   NonNumericVersions(Package),
   NotSemver(Package),
+  LeadingZeroInVersion(Package),
+  InvalidPreRelease(Package),
+  InvalidBuildMetadata(Package),
+  PreReleaseChannelDowngrade(ValidatedPackage, PreReleaseChannel),
+  AlreadyReleased(ValidatedPackage),
+  // Boxed to keep WaffleError small, as ValidatedPackage is large
+  VersionDowngrade(Box<ValidatedPackage>, Box<ValidatedPackage>),
+  VersionOverflow(ValidatedPackage, BumpType),
+  NoPackageVersion(FileName),
+  WorkspaceRootNotFound(FileName),
+  NotAWorkspace(FileName),
+  InvalidWorkspaceMember(FileName, String),
+  CouldNotReadFile(FileName, String),
+  CouldNotWriteFile(FileName, String),
+  GitCommandFailed(String, String),
+  TagAlreadyExists(String),
+  DirtyWorkingTree(FileName),
+  NotAGitRepository(FileName),
+  InvalidTemplate(String, String),
+  NoReleasableCommits(Option<String>),
+  EmptyUnreleasedChangelog(FileName),
+  TomlFileNotFound(FileName),
+  PackageNotFound(String, FileName),
+}
+
+
+impl WaffleError {
+
+  /// The process exit code for this error. These codes are stable, so scripts can rely on them:
+  ///
+  /// - 2: Usage errors, such as conflicting bump types or invalid templates
+  /// - 3: Files could not be read or written
+  /// - 4: The Cargo.toml could not be parsed or doesn't have the expected layout
+  /// - 5: A version is not a valid Semantic Version
+  /// - 6: A version change was refused, such as a downgrade or a bump that overflows
+  /// - 7: A Git command failed or Git is not in the expected state, such as there being no repository
+  /// - 8: There is nothing to release, according to the commits or the changelog
+  pub fn exit_code(&self) -> u8 {
+    match self {
+      WaffleError::TooManyBumpCombinations |
+      WaffleError::NoBumpCombinations |
+      WaffleError::InvalidTemplate(..) => 2,
+
+      WaffleError::CouldNotReadTomlFile(..) |
+      WaffleError::TomlFileNotFound(_) |
+      WaffleError::CouldNotReadFile(..) |
+      WaffleError::CouldNotWriteFile(..) => 3,
+
+      WaffleError::CouldParseTomlFile(..) |
+      WaffleError::CouldConvertTomlContentToDocument(..) |
+      WaffleError::NoPackageVersion(_) |
+      WaffleError::WorkspaceRootNotFound(_) |
+      WaffleError::NotAWorkspace(_) |
+      WaffleError::InvalidWorkspaceMember(..) |
+      WaffleError::PackageNotFound(..) => 4,
+
+      WaffleError::NonNumericVersions(_) |
+      WaffleError::NotSemver(_) |
+      WaffleError::LeadingZeroInVersion(_) |
+      WaffleError::InvalidPreRelease(_) |
+      WaffleError::InvalidBuildMetadata(_) => 5,
+
+      WaffleError::PreReleaseChannelDowngrade(..) |
+      WaffleError::AlreadyReleased(_) |
+      WaffleError::VersionDowngrade(..) |
+      WaffleError::VersionOverflow(..) => 6,
+
+      WaffleError::GitCommandFailed(..) |
+      WaffleError::TagAlreadyExists(_) |
+      WaffleError::DirtyWorkingTree(_) |
+      WaffleError::NotAGitRepository(_) => 7,
+
+      WaffleError::NoReleasableCommits(_) |
+      WaffleError::EmptyUnreleasedChangelog(_) => 8,
+    }
+  }
 }
 
 
@@ -58,19 +134,63 @@ impl fmt::Display for WaffleError {
     let result = match self {
       WaffleError::CouldNotReadTomlFile(filename, error) => s!("Could not read Toml file: {filename}, due to error: {error}"),
 
-      WaffleError::CouldNotUpdateTomlFile(filename, content, error) => s!("Could not update Toml file: {filename}, with content:\n{content} due to error:\n{error}"),
+      WaffleError::TomlFileNotFound(dir) => s!("Could not find a Cargo.toml in: {dir} or any of its parent directories. Use --toml-file to supply one."),
 
       WaffleError::CouldParseTomlFile(filename, error) => s!("Could not parse Toml file: {filename}, due to error: {error}"),
 
       WaffleError::CouldConvertTomlContentToDocument(filename, content, error) => s!("Could not parse Toml file: {filename} into Toml document. \nContent: {content}\nerror: {error}"),
 
-      WaffleError::TooManyBumpCombinations => "Only one of Major, Minor or Patch is allowed. Supply a single bump type.".to_owned(),
+      WaffleError::TooManyBumpCombinations => "Only one of Major, Minor or Patch is allowed and Release can't be combined with any other bump type. Supply a single bump type.".to_owned(),
 
-      WaffleError::NoBumpCombinations => "At least one of Major, Minor or Patch is required".to_owned(),
+      WaffleError::NoBumpCombinations => "At least one of Major, Minor, Patch or a pre-release channel is required".to_owned(),
 
       WaffleError::NonNumericVersions(package) => s!("Toml package.version: {package} is not numeric. Waffle only supports Semantic Versioning."),
 
-      WaffleError::NotSemver(package) => s!("Toml package.version: {package} is not a valid Semantic Version with format: major.minor.patch. Waffle only supports Semantic Versioning."),
+      WaffleError::NotSemver(package) => s!("Toml package.version: {package} is not a valid Semantic Version with format: major.minor.patch[-pre-release][+build]. Waffle only supports Semantic Versioning."),
+
+      WaffleError::LeadingZeroInVersion(package) => s!("Toml package.version: {package} has numeric components with leading zeros, which are not allowed by Semantic Versioning."),
+
+      WaffleError::InvalidPreRelease(package) => s!("Toml package.version: {package} has an invalid pre-release. Pre-release identifiers must be non-empty, use only [0-9A-Za-z-] and numeric identifiers must not have leading zeros."),
+
+      WaffleError::InvalidBuildMetadata(package) => s!("Toml package.version: {package} has invalid build metadata. Build identifiers must be non-empty and use only [0-9A-Za-z-]."),
+
+      WaffleError::PreReleaseChannelDowngrade(version, channel) => s!("Could not move version: {version} to the {channel} pre-release channel, as it would lower the version. Supply a base bump type to start a new pre-release."),
+
+      WaffleError::AlreadyReleased(version) => s!("Version: {version} is already a release version. Only pre-release versions can be released."),
+
+      WaffleError::VersionDowngrade(current, next) => s!("Version: {next} is lower than the current version: {current}. Use --allow-downgrade to set it anyway."),
+
+      WaffleError::VersionOverflow(version, bump_type) => s!("Could not apply a {bump_type} bump to version: {version}, as it would overflow a version component."),
+
+      WaffleError::NoPackageVersion(filename) => s!("Toml file: {filename} has neither a package.version nor a workspace.package.version"),
+
+      WaffleError::WorkspaceRootNotFound(filename) => s!("Toml file: {filename} inherits its version from the workspace, but no workspace root Cargo.toml could be found above it"),
+
+      WaffleError::NotAWorkspace(filename) => s!("Toml file: {filename} is not part of a workspace. Could not find a Cargo.toml with a [workspace] table"),
+
+      WaffleError::PackageNotFound(name, filename) => s!("Package: {name} is not a member of the workspace at: {filename}"),
+
+      WaffleError::InvalidWorkspaceMember(filename, error) => s!("Workspace member pattern in Toml file: {filename} is invalid, due to error: {error}"),
+
+      WaffleError::CouldNotReadFile(filename, error) => s!("Could not read file: {filename}, due to error: {error}"),
+
+      WaffleError::CouldNotWriteFile(filename, error) => s!("Could not write file: {filename}, due to error: {error}. No files were changed."),
+
+      WaffleError::GitCommandFailed(command, error) => s!("Git command: `{command}` failed, due to error: {error}"),
+
+      WaffleError::TagAlreadyExists(tag) => s!("Git tag: {tag} already exists"),
+
+      WaffleError::DirtyWorkingTree(dir) => s!("Git working tree at: {dir} has uncommitted changes. Commit or stash them before tagging."),
+
+      WaffleError::NotAGitRepository(dir) => s!("Directory: {dir} is not in a Git repository. Run git init or bump without --commit."),
+
+      WaffleError::NoReleasableCommits(Some(tag)) => s!("There are no feat, fix or breaking change commits since tag: {tag}. Supply a bump type instead of --auto."),
+
+      WaffleError::NoReleasableCommits(None) => "There are no feat, fix or breaking change commits. Supply a bump type instead of --auto.".to_owned(),
+
+      WaffleError::EmptyUnreleasedChangelog(filename) => s!("Changelog: {filename} has nothing under its [Unreleased] section. Add the changes in this release or use --allow-empty-changelog."),
+
+      WaffleError::InvalidTemplate(template, placeholder) => s!("Template: {template} has an unknown, unavailable or unterminated placeholder: {placeholder}"),
     };
 
     write!(f, "{}", result)
@@ -0,0 +1,233 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::error::{FileName, ResultW, WaffleError};
+
+/// Runs git in the supplied directory, returning its trimmed stdout.
+fn run_git(dir: &Path, args: &[&str]) -> ResultW<String> {
+  run_git_with_exit_codes(dir, args, &[0])
+}
+
+
+/// Like run_git, but any of the supplied exit codes is treated as success.
+fn run_git_with_exit_codes(dir: &Path, args: &[&str], exit_codes: &[i32]) -> ResultW<String> {
+  let command = format!("git {}", args.join(" "));
+
+  let output =
+    Command::new("git")
+      .arg("-C")
+      .arg(dir)
+      .args(args)
+      .output()
+      .map_err(|e| WaffleError::GitCommandFailed(command.clone(), e.to_string()))?;
+
+  if output.status.code().is_some_and(|code| exit_codes.contains(&code)) {
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+  } else {
+    Err(WaffleError::GitCommandFailed(command, String::from_utf8_lossy(&output.stderr).trim().to_owned()))
+  }
+}
+
+
+/// Fails if the directory is not inside a Git repository.
+pub fn ensure_repository(dir: &Path) -> ResultW<()> {
+  run_git(dir, &["rev-parse", "--git-dir"])
+    .map(|_| ())
+    .map_err(|_| WaffleError::NotAGitRepository(FileName::new(dir)))
+}
+
+
+/// Returns the files that are not ignored by Git, as ignored files can't be staged. Tracked files are never ignored.
+pub fn without_ignored_files(dir: &Path, files: &[PathBuf]) -> ResultW<Vec<PathBuf>> {
+  let file_args: Vec<_> = files.iter().map(|f| f.to_string_lossy().to_string()).collect();
+  let file_args: Vec<_> = file_args.iter().map(String::as_str).collect();
+
+  // check-ignore exits with 1 when none of the files are ignored
+  let ignored_files = run_git_with_exit_codes(dir, &[&["check-ignore", "--"], &file_args[..]].concat(), &[0, 1])?;
+  let ignored_files: Vec<_> = ignored_files.lines().map(PathBuf::from).collect();
+
+  Ok(files.iter().filter(|file| !ignored_files.contains(file)).cloned().collect())
+}
+
+
+/// A working tree is dirty if any tracked files have uncommitted changes. Untracked files are ignored.
+pub fn is_working_tree_dirty(dir: &Path) -> ResultW<bool> {
+  let status = run_git(dir, &["status", "--porcelain", "--untracked-files=no"])?;
+  Ok(!status.is_empty())
+}
+
+
+pub fn tag_exists(dir: &Path, tag: &str) -> ResultW<bool> {
+  let tags = run_git(dir, &["tag", "--list", tag])?;
+  Ok(tags.lines().any(|t| t == tag))
+}
+
+
+/// Creates a lightweight tag at HEAD, or an annotated tag if a message is supplied.
+pub fn create_tag(dir: &Path, tag: &str, message: Option<&str>) -> ResultW<()> {
+  match message {
+    Some(message) => run_git(dir, &["tag", "--annotate", tag, "--message", message]),
+    None => run_git(dir, &["tag", tag]),
+  }?;
+
+  Ok(())
+}
+
+
+/// Stages and commits only the supplied files, leaving anything else that is staged uncommitted.
+/// Returns the hash of the new commit. If the commit fails, the files are unstaged again.
+pub fn commit_files(dir: &Path, files: &[PathBuf], message: &str) -> ResultW<String> {
+  let files: Vec<_> = files.iter().map(|f| f.to_string_lossy().to_string()).collect();
+  let file_args: Vec<_> = files.iter().map(String::as_str).collect();
+
+  run_git(dir, &[&["add", "--"], &file_args[..]].concat())?;
+
+  if let Err(error) = run_git(dir, &[&["commit", "--quiet", "--message", message, "--"], &file_args[..]].concat()) {
+    let _ = run_git(dir, &[&["reset", "--quiet", "--"], &file_args[..]].concat());
+    return Err(error)
+  }
+
+  get_commit(dir, "HEAD")
+}
+
+
+/// Returns the tags reachable from HEAD.
+pub fn get_reachable_tags(dir: &Path) -> ResultW<Vec<String>> {
+  let reachable_tags = run_git(dir, &["tag", "--merged", "HEAD"])?;
+  Ok(reachable_tags.lines().map(str::to_owned).collect())
+}
+
+
+/// Returns the hash and message of each commit reachable from HEAD, newest first. If a tag is supplied, only commits
+/// after the tag are returned.
+pub fn get_commit_messages(dir: &Path, since_tag: Option<&str>) -> ResultW<Vec<(String, String)>> {
+  let range = since_tag.map_or("HEAD".to_owned(), |tag| format!("{tag}..HEAD"));
+  // Fields are separated by the unit separator and commits by the record separator
+  let log = run_git(dir, &["log", "--format=%H%x1f%B%x1e", &range])?;
+
+  let commits =
+    log
+      .split('\x1e')
+      .filter_map(|commit| commit.trim().split_once('\x1f'))
+      .map(|(hash, message)| (hash.to_owned(), message.trim().to_owned()))
+      .collect();
+
+  Ok(commits)
+}
+
+
+/// Returns the hash of the commit a revision points at.
+pub fn get_commit(dir: &Path, revision: &str) -> ResultW<String> {
+  run_git(dir, &["rev-parse", &format!("{revision}^{{commit}}")])
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    fn setup_repository(dir: &Path) {
+      run_git(dir, &["init", "--quiet"]).unwrap();
+      run_git(dir, &["config", "user.name", "waffle"]).unwrap();
+      run_git(dir, &["config", "user.email", "waffle@example.com"]).unwrap();
+      std::fs::write(dir.join("Cargo.toml"), "[package]\nversion = \"1.2.3\"\n").unwrap();
+      run_git(dir, &["add", "Cargo.toml"]).unwrap();
+      run_git(dir, &["commit", "--quiet", "--message", "initial"]).unwrap();
+    }
+
+    #[test]
+    fn creates_lightweight_and_annotated_tags() {
+      let working_dir = tempdir().unwrap();
+      setup_repository(working_dir.path());
+      let head = get_commit(working_dir.path(), "HEAD").unwrap();
+
+      assert!(!tag_exists(working_dir.path(), "v1.2.3").unwrap());
+
+      create_tag(working_dir.path(), "v1.2.3", None).unwrap();
+      create_tag(working_dir.path(), "release-1.2.3", Some("Release 1.2.3")).unwrap();
+
+      assert!(tag_exists(working_dir.path(), "v1.2.3").unwrap());
+      assert_eq!(get_commit(working_dir.path(), "v1.2.3").unwrap(), head);
+      assert_eq!(get_commit(working_dir.path(), "release-1.2.3").unwrap(), head);
+      assert_eq!(run_git(working_dir.path(), &["tag", "--list", "--format=%(contents)", "release-1.2.3"]).unwrap(), "Release 1.2.3")
+    }
+
+    #[test]
+    fn commits_only_supplied_files() {
+      let working_dir = tempdir().unwrap();
+      setup_repository(working_dir.path());
+
+      let toml_file = working_dir.path().join("Cargo.toml");
+      let other_file = working_dir.path().join("other.txt");
+      std::fs::write(&toml_file, "[package]\nversion = \"1.2.4\"\n").unwrap();
+      std::fs::write(&other_file, "other").unwrap();
+      run_git(working_dir.path(), &["add", "other.txt"]).unwrap();
+
+      let commit = commit_files(working_dir.path(), &[toml_file], "Bump version to 1.2.4").unwrap();
+
+      assert_eq!(get_commit(working_dir.path(), "HEAD").unwrap(), commit);
+      assert_eq!(run_git(working_dir.path(), &["show", "--name-only", "--format=%s", "HEAD"]).unwrap(), "Bump version to 1.2.4\n\nCargo.toml");
+      assert_eq!(run_git(working_dir.path(), &["diff", "--cached", "--name-only"]).unwrap(), "other.txt")
+    }
+
+    #[test]
+    fn gets_commit_messages_since_latest_tag() {
+      let working_dir = tempdir().unwrap();
+      setup_repository(working_dir.path());
+      assert_eq!(get_reachable_tags(working_dir.path()).unwrap(), Vec::<String>::new());
+
+      create_tag(working_dir.path(), "v1.2.3", None).unwrap();
+      run_git(working_dir.path(), &["commit", "--quiet", "--allow-empty", "--message", "feat: first\n\ndetails"]).unwrap();
+      run_git(working_dir.path(), &["commit", "--quiet", "--allow-empty", "--message", "fix: second"]).unwrap();
+
+      let reachable_tags = get_reachable_tags(working_dir.path()).unwrap();
+      let messages: Vec<_> =
+        get_commit_messages(working_dir.path(), Some("v1.2.3"))
+          .unwrap()
+          .into_iter()
+          .map(|(_, message)| message)
+          .collect();
+
+      assert_eq!(reachable_tags, vec!["v1.2.3"]);
+      assert_eq!(messages, vec!["fix: second", "feat: first\n\ndetails"]);
+      assert_eq!(get_commit_messages(working_dir.path(), None).unwrap().len(), 3)
+    }
+
+    #[test]
+    fn detects_repository() {
+      let working_dir = tempdir().unwrap();
+      let dir = working_dir.path();
+
+      assert_eq!(ensure_repository(dir), Err(WaffleError::NotAGitRepository(FileName::new(dir))));
+
+      setup_repository(dir);
+      assert_eq!(ensure_repository(dir), Ok(()))
+    }
+
+    #[test]
+    fn removes_ignored_files() {
+      let working_dir = tempdir().unwrap();
+      setup_repository(working_dir.path());
+      std::fs::write(working_dir.path().join(".gitignore"), "Cargo.lock\n").unwrap();
+
+      let toml_file = working_dir.path().join("Cargo.toml");
+      let lock_file = working_dir.path().join("Cargo.lock");
+
+      assert_eq!(without_ignored_files(working_dir.path(), &[toml_file.clone(), lock_file]).unwrap(), vec![toml_file.clone()]);
+      assert_eq!(without_ignored_files(working_dir.path(), std::slice::from_ref(&toml_file)).unwrap(), vec![toml_file])
+    }
+
+    #[test]
+    fn detects_dirty_working_tree() {
+      let working_dir = tempdir().unwrap();
+      setup_repository(working_dir.path());
+
+      std::fs::write(working_dir.path().join("untracked.txt"), "untracked").unwrap();
+      assert!(!is_working_tree_dirty(working_dir.path()).unwrap());
+
+      std::fs::write(working_dir.path().join("Cargo.toml"), "[package]\nversion = \"1.2.4\"\n").unwrap();
+      assert!(is_working_tree_dirty(working_dir.path()).unwrap())
+    }
+}
@@ -0,0 +1,3 @@
+mod git_tools;
+
+pub use git_tools::{commit_files, create_tag, ensure_repository, get_commit, get_commit_messages, get_reachable_tags, is_working_tree_dirty, tag_exists, without_ignored_files};
@@ -0,0 +1,51 @@
+//! Tasty version information from your Cargo.toml file.
+//!
+//! Waffle is a command line application, but its building blocks can also be used as a library: parsing and bumping
+//! Semantic Versions, reading and updating the version of a Cargo.toml in memory and working out tag names.
+//!
+//! ```
+//! use std::path::Path;
+//! use waffle::{BumpType, ValidatedPackage, VersionSource};
+//!
+//! let toml_file = Path::new("Cargo.toml");
+//! let content = "[package]\nname = \"sample\"\nversion = \"1.2.3\"\n";
+//!
+//! let manifest = waffle::parse_cargo_toml(toml_file, content)?;
+//! let VersionSource::Package(version) = manifest.version_source() else { unreachable!() };
+//!
+//! let current_version: ValidatedPackage = version.parse()?;
+//! let next_version = current_version.bump_version(BumpType::Minor)?;
+//! assert_eq!(next_version.to_string(), "1.3.0");
+//!
+//! let updated_content = waffle::update_toml(toml_file, content, next_version.clone())?.to_string();
+//! assert_eq!(updated_content, "[package]\nname = \"sample\"\nversion = \"1.3.0\"\n");
+//!
+//! let tag = waffle::tag_name(waffle::DEFAULT_TAG_TEMPLATE, manifest.package_name().as_deref(), &next_version)?;
+//! assert_eq!(tag, "v1.3.0");
+//! # Ok::<(), waffle::WaffleError>(())
+//! ```
+//!
+//! The command line application itself can be driven with [workflow], which takes its arguments as [Args] rather
+//! than reading them from the process.
+
+mod args;
+mod error;
+mod wtoml;
+mod workflow;
+mod output;
+mod diff;
+mod changeset;
+mod git;
+mod template;
+mod conventional;
+mod changelog;
+
+pub use args::{BumpType, PreReleaseChannel};
+pub use args::cli::{get_cli_args, Args, LogLevel, OutputFormat, WaffleCommands};
+pub use error::{FileName, ResultW, TomlContent, WaffleError};
+pub use wtoml::{CargoPackage, CargoToml, Metadata, Package, PackageVersion, PreReleaseIdentifier, TomlData, ValidatedPackage, VersionSource};
+pub use wtoml::{WaffleConfig, Workspace, WorkspacePackage};
+pub use wtoml::{get_current_version, parse_cargo_toml, update_toml};
+pub use template::{tag_name, DEFAULT_TAG_TEMPLATE};
+pub use output::{Console, NextVersion, Output, VersionBump};
+pub use workflow::{perform_workflow, workflow};
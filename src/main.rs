@@ -1,11 +1,12 @@
+use std::process::ExitCode;
 
-mod args;
-mod error;
-mod wtoml;
-mod workflow;
-mod output;
-mod diff;
+fn main() -> ExitCode {
+  let args = waffle::get_cli_args();
 
-fn main() {
-  crate::workflow::perform_workflow()
+  env_logger::Builder::new()
+    .filter_level(args.log_level_filter())
+    .format_timestamp(None)
+    .init();
+
+  waffle::perform_workflow(args)
 }
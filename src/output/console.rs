@@ -0,0 +1,48 @@
+use std::{println as p, eprintln as e};
+use std::fmt::Display;
+
+use serde_json::json;
+
+use crate::args::OutputFormat;
+use crate::error::WaffleError;
+use super::Output;
+
+/// Writes results, errors and informational messages, such as the toml file in use and diffs, in the chosen format.
+///
+/// The result is the only thing written to stdout, so it can be captured by scripts. Informational messages are
+/// written to stderr, unless quiet or the format is json, in which case stderr only ever holds the JSON error.
+#[derive(Debug, Clone, Copy)]
+pub struct Console {
+  format: OutputFormat,
+  quiet: bool,
+}
+
+impl Console {
+
+  pub fn new(format: OutputFormat, quiet: bool) -> Self {
+    Self {
+      format,
+      quiet,
+    }
+  }
+
+  pub fn info<T: Display>(&self, message: T) {
+    if !self.quiet && self.format == OutputFormat::Text {
+      e!("{message}")
+    }
+  }
+
+  pub fn output(&self, output: &Output) {
+    match self.format {
+      OutputFormat::Text => p!("{output}"),
+      OutputFormat::Json => p!("{}", serde_json::to_string(output).unwrap_or_else(|e| json!({ "error": e.to_string() }).to_string())),
+    }
+  }
+
+  pub fn error(&self, error: &WaffleError) {
+    match self.format {
+      OutputFormat::Text => e!("{error}"),
+      OutputFormat::Json => e!("{}", json!({ "error": { "exit_code": error.exit_code(), "message": error.to_string() } })),
+    }
+  }
+}
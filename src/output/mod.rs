@@ -1,5 +1,5 @@
 mod model;
+mod console;
 
-pub use model::Output;
-
-
+pub use model::{NextVersion, Output, VersionBump};
+pub use console::Console;
@@ -1,20 +1,126 @@
 use std::format as s;
 use std::fmt;
+use std::path::PathBuf;
 
-use crate::wtoml::{Package, ValidatedPackage};
+use crate::args::BumpType;
+use crate::wtoml::ValidatedPackage;
 
+#[derive(Debug, serde::Serialize)]
+#[serde(tag = "output", rename_all = "kebab-case")]
 pub enum Output {
-  Version(Package),
-  Tag(Package),
-  Bump(ValidatedPackage, ValidatedPackage)
+  /// The current version
+  Version {
+    toml_file: PathBuf,
+    name: Option<String>,
+    version: String,
+  },
+  /// The tag name for the current version
+  Tag {
+    toml_file: PathBuf,
+    name: Option<String>,
+    version: ValidatedPackage,
+    tag: String,
+  },
+  /// The created tag and the commit it points at
+  TagCreated {
+    toml_file: PathBuf,
+    name: Option<String>,
+    version: ValidatedPackage,
+    tag: String,
+    commit: String,
+  },
+  /// A single version update, along with the commit and tag it was released with.
+  /// On a dry run, no files were changed and the tag is the one that would have been created.
+  Bump {
+    #[serde(flatten)]
+    bump: VersionBump,
+    files_changed: Vec<PathBuf>,
+    commit: Option<String>,
+    tag: Option<String>,
+    dry_run: bool,
+  },
+  WorkspaceBump {
+    bumps: Vec<VersionBump>,
+    files_changed: Vec<PathBuf>,
+    dry_run: bool,
+  },
+  /// The version a single bump type would produce
+  NextVersion {
+    toml_file: PathBuf,
+    name: Option<String>,
+    current_version: ValidatedPackage,
+    #[serde(flatten)]
+    next_version: NextVersion,
+  },
+  /// The versions each bump type would produce. Bump types that can't be applied to the current version are left out.
+  NextVersions {
+    toml_file: PathBuf,
+    name: Option<String>,
+    current_version: ValidatedPackage,
+    next_versions: Vec<NextVersion>,
+  },
 }
 
+
+/// The version a bump type would produce
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct NextVersion {
+  pub bump_type: BumpType,
+  pub next_version: ValidatedPackage,
+}
+
+
+/// The version update of a toml file
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct VersionBump {
+  pub toml_file: PathBuf,
+  pub name: Option<String>,
+  pub old_version: ValidatedPackage,
+  pub new_version: ValidatedPackage,
+}
+
+
+const DRY_RUN_MESSAGE: &str = "Dry run: no files were changed";
+
 impl fmt::Display for Output {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
       let output = match self {
-        Output::Version(Package { version }) => version.to_owned(),
-        Output::Tag(Package { version }) => s!("git tag v{}", version),
-        Output::Bump(before, after) => s!("Updated version from: {before} -> {after}"),
+        Output::Version { version, .. } => version.to_owned(),
+        Output::Tag { tag, .. } => s!("git tag {tag}"),
+        Output::TagCreated { tag, commit, .. } => s!("Created tag {tag} at commit {commit}"),
+        Output::Bump { bump, files_changed, commit, tag, dry_run } => {
+          let mut lines = vec![s!("Updated version from: {} -> {}", bump.old_version, bump.new_version)];
+
+          if let Some(commit) = commit {
+            lines.push(s!("Committed {} file(s) at commit {commit}", files_changed.len()));
+
+            if let Some(tag) = tag {
+              lines.push(s!("Created tag {tag} at commit {commit}"))
+            }
+          }
+
+          if *dry_run {
+            lines.push(DRY_RUN_MESSAGE.to_owned())
+          }
+
+          lines.join("\n")
+        },
+        Output::WorkspaceBump { bumps, dry_run, .. } =>
+          bumps
+            .iter()
+            .map(|bump| s!("Updated version of {} from: {} -> {}", bump.toml_file.to_string_lossy(), bump.old_version, bump.new_version))
+            .chain(dry_run.then(|| DRY_RUN_MESSAGE.to_owned()))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Output::NextVersion { next_version, .. } => next_version.next_version.to_string(),
+        Output::NextVersions { current_version, next_versions, .. } => {
+          let width = next_versions.iter().map(|next| next.bump_type.to_string().len()).max().unwrap_or_default();
+
+          std::iter::once(s!("{:width$}  {current_version}", "current"))
+            .chain(next_versions.iter().map(|next| s!("{:width$}  {}", next.bump_type.to_string(), next.next_version)))
+            .collect::<Vec<_>>()
+            .join("\n")
+        },
       };
 
       write!(f, "{output}")
@@ -0,0 +1,3 @@
+mod template_tools;
+
+pub use template_tools::{commit_message, tag_message, tag_name, tag_version, DEFAULT_COMMIT_MESSAGE_TEMPLATE, DEFAULT_TAG_TEMPLATE};
@@ -0,0 +1,151 @@
+use crate::error::{ResultW, WaffleError};
+use crate::wtoml::ValidatedPackage;
+
+pub const DEFAULT_TAG_TEMPLATE: &str = "v{version}";
+
+pub const DEFAULT_COMMIT_MESSAGE_TEMPLATE: &str = "Bump version to {new_version}";
+
+
+/// Replaces each {placeholder} in the template with its value.
+/// Fails if the template uses a placeholder without a value.
+pub fn render_template(template: &str, placeholders: &[(&str, String)]) -> ResultW<String> {
+  let mut rendered = String::with_capacity(template.len());
+  let mut remaining = template;
+
+  while let Some(start) = remaining.find('{') {
+    rendered.push_str(&remaining[..start]);
+
+    let end =
+      remaining[start..]
+        .find('}')
+        .map(|end| start + end)
+        .ok_or_else(|| WaffleError::InvalidTemplate(template.to_owned(), remaining[start..].to_owned()))?;
+
+    let placeholder = &remaining[start + 1..end];
+    let value =
+      placeholders
+        .iter()
+        .find(|(name, _)| *name == placeholder)
+        .map(|(_, value)| value)
+        .ok_or_else(|| WaffleError::InvalidTemplate(template.to_owned(), remaining[start..=end].to_owned()))?;
+
+    rendered.push_str(value);
+    remaining = &remaining[end + 1..];
+  }
+
+  rendered.push_str(remaining);
+
+  Ok(rendered)
+}
+
+
+/// The {name}, {version}, {major}, {minor} and {patch} placeholders of a crate version.
+/// {name} is only available for crates that have a name.
+pub fn version_placeholders(name: Option<&str>, version: &ValidatedPackage) -> Vec<(&'static str, String)> {
+  let mut placeholders =
+    vec![
+      ("version", version.to_string()),
+      ("major", version.major.to_string()),
+      ("minor", version.minor.to_string()),
+      ("patch", version.patch.to_string()),
+    ];
+
+  if let Some(name) = name {
+    placeholders.push(("name", name.to_owned()))
+  }
+
+  placeholders
+}
+
+
+/// Renders the tag name for a version, such as v1.2.3 with the default template.
+pub fn tag_name(template: &str, name: Option<&str>, version: &ValidatedPackage) -> ResultW<String> {
+  render_template(template, &version_placeholders(name, version))
+}
+
+
+/// Parses a tag back into the version it was created for, or None if the template could not have produced it.
+pub fn tag_version(template: &str, name: Option<&str>, tag: &str) -> Option<ValidatedPackage> {
+  // The version may be anywhere in the tag, so try each substring that parses as a version
+  (0..tag.len())
+    .flat_map(|start| (start + 1..=tag.len()).map(move |end| (start, end)))
+    .filter(|(start, end)| tag.is_char_boundary(*start) && tag.is_char_boundary(*end))
+    .filter_map(|(start, end)| tag[start..end].parse::<ValidatedPackage>().ok())
+    .find(|version| tag_name(template, name, version).is_ok_and(|rendered| rendered == tag))
+}
+
+
+/// Annotated tag messages support the {tag} placeholder along with those of the tag name.
+pub fn tag_message(template: &str, tag: &str, name: Option<&str>, version: &ValidatedPackage) -> ResultW<String> {
+  let mut placeholders = version_placeholders(name, version);
+  placeholders.push(("tag", tag.to_owned()));
+
+  render_template(template, &placeholders)
+}
+
+
+/// Commit messages support the {name}, {old_version} and {new_version} placeholders.
+pub fn commit_message(template: &str, name: Option<&str>, old_version: &ValidatedPackage, new_version: &ValidatedPackage) -> ResultW<String> {
+  let mut placeholders =
+    vec![
+      ("old_version", old_version.to_string()),
+      ("new_version", new_version.to_string()),
+    ];
+
+  if let Some(name) = name {
+    placeholders.push(("name", name.to_owned()))
+  }
+
+  render_template(template, &placeholders)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn renders_tag_templates() {
+      let version = ValidatedPackage::new("1.2.3-rc.1");
+
+      assert_eq!(tag_name(DEFAULT_TAG_TEMPLATE, None, &version).unwrap(), "v1.2.3-rc.1");
+      assert_eq!(tag_name("{name}-v{version}", Some("mycrate"), &version).unwrap(), "mycrate-v1.2.3-rc.1");
+      assert_eq!(tag_name("{name}/{major}.{minor}.{patch}", Some("mycrate"), &version).unwrap(), "mycrate/1.2.3");
+      assert_eq!(tag_name("release", None, &version).unwrap(), "release");
+    }
+
+    #[test]
+    fn parses_tag_versions() {
+      assert_eq!(tag_version(DEFAULT_TAG_TEMPLATE, None, "v1.2.3-rc.1"), Some(ValidatedPackage::new("1.2.3-rc.1")));
+      assert_eq!(tag_version("{name}-v{version}", Some("mycrate"), "mycrate-v1.2.3"), Some(ValidatedPackage::new("1.2.3")));
+      assert_eq!(tag_version("{name}/{major}.{minor}.{patch}", Some("mycrate"), "mycrate/1.2.3"), Some(ValidatedPackage::new("1.2.3")));
+      assert_eq!(tag_version(DEFAULT_TAG_TEMPLATE, None, "docs-published"), None);
+      assert_eq!(tag_version(DEFAULT_TAG_TEMPLATE, None, "other-v1.2.3"), None);
+      assert_eq!(tag_version("{name}-v{version}", Some("mycrate"), "other-v1.2.3"), None);
+    }
+
+    #[test]
+    fn renders_tag_messages() {
+      let message = tag_message("Release {name} {version} as {tag}", "mycrate-v1.2.3", Some("mycrate"), &ValidatedPackage::new("1.2.3")).unwrap();
+
+      assert_eq!(message, "Release mycrate 1.2.3 as mycrate-v1.2.3")
+    }
+
+    #[test]
+    fn renders_commit_messages() {
+      let message = commit_message("Bump {name} from {old_version} to {new_version}", Some("mycrate"), &ValidatedPackage::new("1.2.3"), &ValidatedPackage::new("1.3.0")).unwrap();
+
+      assert_eq!(message, "Bump mycrate from 1.2.3 to 1.3.0");
+      assert_eq!(commit_message(DEFAULT_COMMIT_MESSAGE_TEMPLATE, None, &ValidatedPackage::new("1.2.3"), &ValidatedPackage::new("1.3.0")).unwrap(), "Bump version to 1.3.0")
+    }
+
+    #[test]
+    fn fails_on_unavailable_placeholders() {
+      let version = ValidatedPackage::new("1.2.3");
+
+      assert_eq!(tag_name("{name}-v{version}", None, &version), Err(WaffleError::InvalidTemplate("{name}-v{version}".to_owned(), "{name}".to_owned())));
+      assert_eq!(tag_name("v{build}", None, &version), Err(WaffleError::InvalidTemplate("v{build}".to_owned(), "{build}".to_owned())));
+      assert_eq!(tag_name("v{version", None, &version), Err(WaffleError::InvalidTemplate("v{version".to_owned(), "{version".to_owned())));
+    }
+}
@@ -1,3 +1,3 @@
 mod workflow_tools;
 
-pub use workflow_tools::perform_workflow;
+pub use workflow_tools::{perform_workflow, workflow};
@@ -1,51 +1,541 @@
-use std::{println as p, eprintln as e};
+use std::format as s;
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use log::{debug, warn};
 
-use crate::args::{cli, BumpType};
-use crate::error::ResultW;
-use crate::wtoml::{self, TomlData, ValidatedPackage};
-use crate::output::Output;
-use crate::diff::show_diff;
+use crate::args::{cli, BumpType, PreReleaseChannel};
+use crate::error::{FileName, ResultW, WaffleError};
+use crate::wtoml::{self, Package, TomlData, UpdatedCrate, ValidatedPackage};
+use crate::output::{Console, NextVersion, Output, VersionBump};
+use crate::diff::render_diff;
+use crate::changeset::{Changeset, restore_file_changes, write_file_changes};
+use crate::git;
+use crate::template;
+use crate::conventional::{self, ConventionalCommit};
+use crate::changelog;
 
 
-pub fn perform_workflow() {
-  match workflow() {
-    Ok(value) => p!("{value}"),
-    Err(error) => e!("{error}"),
+/// Runs waffle with the supplied arguments, printing its output and returning its exit code.
+/// Logging is left to the caller, so any logger that is installed is used.
+pub fn perform_workflow(args: cli::Args) -> ExitCode {
+  let console = Console::new(args.output, args.quiet);
+
+  match workflow(args, &console) {
+    Ok(value) => {
+      console.output(&value);
+      ExitCode::SUCCESS
+    },
+
+    Err(error) => {
+      console.error(&error);
+      ExitCode::from(error.exit_code())
+    },
   }
 }
 
 
-pub fn workflow() -> ResultW<Output> {
-  let args = cli::get_cli_args();
-
-  let toml_file = wtoml::get_toml_file(args.toml_file);
-  p!("Using toml file: {}", toml_file.to_string_lossy());
+/// Runs waffle with the supplied arguments. Progress is reported through the console and the result is returned
+/// rather than printed.
+pub fn workflow(args: cli::Args, console: &Console) -> ResultW<Output> {
+  let toml_file = wtoml::get_toml_file(args.toml_file.clone())?;
+  let toml_file = if args.workspace_root { wtoml::get_workspace_toml_file(&toml_file)? } else { toml_file };
+  let toml_file = match args.package_name() {
+    Some(package) => wtoml::get_workspace_member(&wtoml::get_workspace_toml_file(&toml_file)?, package)?,
+    None => toml_file,
+  };
+  let tag_template = args.tag_template;
+  console.info(s!("Using toml file: {}", toml_file.to_string_lossy()));
 
-  let toml_data = wtoml::get_current_version(&toml_file)?;
   match args.commands {
     cli::WaffleCommands::Get => {
       // We don't convert this to a ValidatePackage as we are just returning the existing version.
-      Ok(Output::Version(toml_data.package))
+      let TomlData { package: Package { name, version }, toml_file } = get_current_version(&toml_file, console)?;
+      Ok(Output::Version { toml_file, name, version })
+    },
+
+    // A workspace root need not have a version of its own, so we don't read the current version up front.
+    cli::WaffleCommands::Bump{ major, minor, patch, pre, release, workspace: true, no_diff, dry_run, .. } => {
+      let bump_type = BumpType::get_bump_type(major, minor, patch, pre, release)?;
+      bump_workspace(&toml_file, bump_type, no_diff, dry_run, console)
+    },
+
+    cli::WaffleCommands::Bump{ major, minor, patch, pre, release, workspace: false, auto, commit, commit_message, tag, changelog, allow_empty_changelog, no_diff, dry_run } => {
+      let TomlData { package, toml_file: version_toml_file } = get_current_version(&toml_file, console)?;
+      let name = package.name.clone();
+      let validated_current_version: ValidatedPackage = package.try_into()?;
+
+      let bump_type = if auto {
+        infer_bump_type(&toml_file, tag_template.clone(), name.as_deref(), &validated_current_version, pre, console)?
+      } else {
+        BumpType::get_bump_type(major, minor, patch, pre, release)?
+      };
+
+      debug!("Bumping {validated_current_version} with bump type: {bump_type:?}");
+      let next_version = validated_current_version.bump_version(bump_type)?;
+
+      // Work out the commit message and tag before changing any files, so that invalid templates or an existing tag
+      // don't leave us with a half finished bump.
+      let commit_details =
+        commit
+          .then(|| get_commit_details(&toml_file, commit_message, tag.then(|| tag_template.clone()), name.as_deref(), &validated_current_version, &next_version))
+          .transpose()?;
+
+      let bump =
+        VersionBump {
+          toml_file: version_toml_file,
+          name: name.clone(),
+          old_version: validated_current_version.clone(),
+          new_version: next_version.clone(),
+        };
+
+      let mut changeset = version_changes(std::slice::from_ref(&bump))?;
+
+      if changelog {
+        add_changelog_entry(&mut changeset, &toml_file, tag_template.clone(), name.as_deref(), &validated_current_version, &next_version)?;
+      } else {
+        release_unreleased_changes(&mut changeset, &toml_file, tag_template.clone(), name.as_deref(), &next_version, allow_empty_changelog)?;
+      }
+
+      let mut files_changed = write_changes(&changeset, no_diff, dry_run, console)?;
+
+      let (commit, tag) = match commit_details {
+        Some((message, tag)) if dry_run => {
+          console.info(s!("Would commit {} file(s) with message: {message}", files_changed.len()));
+          if let Some(tag) = &tag {
+            console.info(s!("Would create tag: {tag}"));
+          }
+
+          (None, tag)
+        },
+
+        Some((message, tag)) => {
+          let dir = git_dir(&toml_file);
+          let (files_committed, commit) = commit_changes(dir, &changeset, &files_changed, &message, console)?;
+          files_changed = files_committed;
+
+          if let Some(tag) = &tag {
+            git::create_tag(dir, tag, None)?;
+          }
+
+          (Some(commit), tag)
+        },
+
+        None => (None, None),
+      };
+
+      Ok(Output::Bump { bump, files_changed, commit, tag, dry_run })
     },
 
-    cli::WaffleCommands::Bump{ major, minor, patch, no_diff } => {
-      let TomlData { package, content } = toml_data;
-      let bump_type = BumpType::get_bump_type(major, minor, patch)?;
+    cli::WaffleCommands::Next { major, minor, patch, pre, release } => {
+      let TomlData { package, toml_file } = get_current_version(&toml_file, console)?;
+      let name = package.name.clone();
+      let current_version: ValidatedPackage = package.try_into()?;
+
+      match BumpType::get_bump_type(major, minor, patch, pre, release) {
+        Err(WaffleError::NoBumpCombinations) => {
+          let next_versions =
+            BumpType::all()
+              .into_iter()
+              .filter_map(|bump_type| next_version(&current_version, bump_type).ok())
+              .collect();
+
+          Ok(Output::NextVersions { toml_file, name, current_version, next_versions })
+        },
+
+        bump_type => {
+          let next_version = next_version(&current_version, bump_type?)?;
+          Ok(Output::NextVersion { toml_file, name, current_version, next_version })
+        },
+      }
+    },
+
+    cli::WaffleCommands::Set { version, allow_downgrade, no_diff } => {
+      let TomlData { package, toml_file, .. } = get_current_version(&toml_file, console)?;
+      let name = package.name.clone();
       let validated_current_version: ValidatedPackage = package.try_into()?;
-      let next_version = validated_current_version.bump_version(bump_type);
-      let new_content = wtoml::write_updated_version(toml_file, &content, next_version.clone())?;
+      let next_version: ValidatedPackage = Package { name: None, version }.try_into()?;
 
-      if  !no_diff {
-        show_diff(&content, &new_content)
+      if !allow_downgrade && next_version.cmp_precedence(&validated_current_version) == Ordering::Less {
+        return Err(WaffleError::VersionDowngrade(Box::new(validated_current_version), Box::new(next_version)))
       }
 
-      Ok(Output::Bump(validated_current_version, next_version))
+      let bump =
+        VersionBump {
+          toml_file,
+          name,
+          old_version: validated_current_version,
+          new_version: next_version,
+        };
+
+      let changeset = version_changes(std::slice::from_ref(&bump))?;
+      let files_changed = write_changes(&changeset, no_diff, false, console)?;
+
+      Ok(Output::Bump { bump, files_changed, commit: None, tag: None, dry_run: false })
     },
 
-    cli::WaffleCommands::Tag => {
-      // We don't convert this to a ValidatePackage as we are just returning the existing version.
-      // Output's Display instance will handle writing out the correct String
-      Ok(Output::Tag(toml_data.package))
+    cli::WaffleCommands::Tag { create, annotated, message } => {
+      let TomlData { package, toml_file: version_toml_file } = get_current_version(&toml_file, console)?;
+      let tag_template = get_tag_template(tag_template, &toml_file)?;
+      let name = package.name.clone();
+      let version: ValidatedPackage = package.try_into()?;
+      let tag = template::tag_name(&tag_template, name.as_deref(), &version)?;
+
+      if create {
+        let message = annotated.then(|| template::tag_message(&message, &tag, name.as_deref(), &version)).transpose()?;
+        let commit = create_tag(&toml_file, &tag, message.as_deref())?;
+        Ok(Output::TagCreated { toml_file: version_toml_file, name, version, tag, commit })
+      } else {
+        // Output's Display instance will handle writing out the correct String
+        Ok(Output::Tag { toml_file: version_toml_file, name, version, tag })
+      }
+    },
+  }
+}
+
+
+fn next_version(current_version: &ValidatedPackage, bump_type: BumpType) -> ResultW<NextVersion> {
+  let next_version =
+    NextVersion {
+      next_version: current_version.bump_version(bump_type.clone())?,
+      bump_type,
+    };
+
+  Ok(next_version)
+}
+
+
+/// The tag template from the command line, then the config, then the default.
+fn get_tag_template(tag_template: Option<String>, toml_file: &Path) -> ResultW<String> {
+  match tag_template {
+    Some(tag_template) => Ok(tag_template),
+    None => {
+      let config = wtoml::get_config(toml_file)?;
+      Ok(config.tag_template.unwrap_or(template::DEFAULT_TAG_TEMPLATE.to_owned()))
+    },
+  }
+}
+
+
+/// Finds the commits since the tag of the current version. If there's no such tag, the nearest tag is used and if there
+/// are no tags at all, the whole history is used.
+fn get_commits_since_last_tag(toml_file: &Path, tag_template: Option<String>, name: Option<&str>, current_version: &ValidatedPackage) -> ResultW<(Option<String>, Vec<ConventionalCommit>)> {
+  let dir = git_dir(toml_file);
+  let tag_template = get_tag_template(tag_template, toml_file)?;
+  let current_version_tag = template::tag_name(&tag_template, name, current_version)?;
+
+  // Only tags created by the tag template are release tags, so other tags such as those of other crates are skipped
+  let since_tag =
+    if git::tag_exists(dir, &current_version_tag)? {
+      Some(current_version_tag)
+    } else {
+      git::get_reachable_tags(dir)?
+        .into_iter()
+        .filter_map(|tag| template::tag_version(&tag_template, name, &tag).map(|version| (version, tag)))
+        .max_by(|(version, _), (other_version, _)| version.cmp_precedence(other_version))
+        .map(|(_, tag)| tag)
+    };
+
+  let commits =
+    git::get_commit_messages(dir, since_tag.as_deref())?
+      .iter()
+      .map(|(hash, message)| ConventionalCommit::new(hash, message))
+      .collect();
+
+  Ok((since_tag, commits))
+}
+
+
+fn infer_bump_type(toml_file: &Path, tag_template: Option<String>, name: Option<&str>, current_version: &ValidatedPackage, pre: Option<PreReleaseChannel>, console: &Console) -> ResultW<BumpType> {
+  let (since_tag, commits) = get_commits_since_last_tag(toml_file, tag_template, name, current_version)?;
+  debug!("Found {} commit(s) since tag: {since_tag:?}", commits.len());
+
+  let (base_bump_type, driving_commits) =
+    conventional::infer_bump_type(&commits, current_version)
+      .ok_or_else(|| WaffleError::NoReleasableCommits(since_tag.clone()))?;
+
+  let bump_type = BumpType::get_bump_type(base_bump_type == BumpType::Major, base_bump_type == BumpType::Minor, base_bump_type == BumpType::Patch, pre, false)?;
+
+  match &since_tag {
+    Some(tag) => console.info(s!("Inferred {bump_type} bump from commits since {tag}:")),
+    None => console.info(s!("Inferred {bump_type} bump from commits:")),
+  }
+
+  for commit in driving_commits {
+    console.info(s!("  {} {}", &commit.hash[..commit.hash.len().min(7)], commit.summary));
+  }
+
+  Ok(bump_type)
+}
+
+
+/// Adds a section for the next version to the CHANGELOG.md next to the toml file, creating it if needed.
+fn add_changelog_entry(changeset: &mut Changeset, toml_file: &Path, tag_template: Option<String>, name: Option<&str>, current_version: &ValidatedPackage, next_version: &ValidatedPackage) -> ResultW<()> {
+  let (_, commits) = get_commits_since_last_tag(toml_file, tag_template, name, current_version)?;
+  let section = changelog::render_release_section(next_version, &changelog::today(), &commits);
+  let changelog_file = git_dir(toml_file).join(changelog::CHANGELOG_FILE);
+
+  changeset.update_or_create_file(changelog_file, |_, content| Ok(changelog::insert_release_section(content, &section)))
+}
+
+
+/// Releases the [Unreleased] section of the CHANGELOG.md next to the toml file as the next version, if there is one.
+fn release_unreleased_changes(changeset: &mut Changeset, toml_file: &Path, tag_template: Option<String>, name: Option<&str>, next_version: &ValidatedPackage, allow_empty_changelog: bool) -> ResultW<()> {
+  let changelog_file = git_dir(toml_file).join(changelog::CHANGELOG_FILE);
+  if !changelog_file.is_file() {
+    return Ok(())
+  }
+
+  let tag_template = get_tag_template(tag_template, toml_file)?;
+  let tag = template::tag_name(&tag_template, name, next_version)?;
+
+  changeset.update_file(changelog_file, |file, content| {
+    match changelog::unreleased_section(content) {
+      None => Ok(content.to_owned()),
+      Some(section) if section.trim().is_empty() && !allow_empty_changelog => Err(WaffleError::EmptyUnreleasedChangelog(FileName::new(file))),
+      Some(_) => Ok(changelog::promote_unreleased_section(content, next_version, &changelog::today(), &tag)),
+    }
+  })
+}
+
+
+/// Returns the commit message and, when tagging, the tag for a bump commit.
+/// tag_template is Some when tagging, holding any tag template supplied on the command line.
+fn get_commit_details(toml_file: &Path, commit_message: Option<String>, tag_template: Option<Option<String>>, name: Option<&str>, current_version: &ValidatedPackage, next_version: &ValidatedPackage) -> ResultW<(String, Option<String>)> {
+  git::ensure_repository(git_dir(toml_file))?;
+
+  let config = wtoml::get_config(toml_file)?;
+  let message_template = commit_message.or(config.commit_message).unwrap_or(template::DEFAULT_COMMIT_MESSAGE_TEMPLATE.to_owned());
+  let message = template::commit_message(&message_template, name, current_version, next_version)?;
+
+  let tag = match tag_template {
+    Some(tag_template) => {
+      let tag_template = get_tag_template(tag_template, toml_file)?;
+      let tag = template::tag_name(&tag_template, name, next_version)?;
+
+      if git::tag_exists(git_dir(toml_file), &tag)? {
+        return Err(WaffleError::TagAlreadyExists(tag))
+      }
+
+      Some(tag)
     },
+
+    None => None,
+  };
+
+  Ok((message, tag))
+}
+
+
+/// Commits the changed files that aren't ignored by Git, returning them with the new commit. If the commit fails, the
+/// changes are restored so that we don't leave a half finished bump behind.
+fn commit_changes(dir: &Path, changeset: &Changeset, files_changed: &[PathBuf], message: &str, console: &Console) -> ResultW<(Vec<PathBuf>, String)> {
+  let commit =
+    git::without_ignored_files(dir, files_changed)
+      .and_then(|files_to_commit| {
+        for ignored_file in files_changed.iter().filter(|file| !files_to_commit.contains(file)) {
+          console.info(s!("Not committing ignored file: {}", ignored_file.to_string_lossy()));
+        }
+
+        let commit = git::commit_files(dir, &files_to_commit, message)?;
+        debug!("Committed {} file(s) with message: {message}", files_to_commit.len());
+        Ok((files_to_commit, commit))
+      });
+
+  if commit.is_err() {
+    warn!("Could not commit changes, restoring {} changed file(s)", changeset.changes().len());
+    restore_file_changes(changeset.changes());
+  }
+
+  commit
+}
+
+
+/// Git commands are run from the directory of the toml file.
+fn git_dir(toml_file: &Path) -> &Path {
+  toml_file.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."))
+}
+
+
+/// Creates the tag at HEAD, returning the commit it points at.
+fn create_tag(toml_file: &Path, tag: &str, message: Option<&str>) -> ResultW<String> {
+  let dir = git_dir(toml_file);
+
+  if git::is_working_tree_dirty(dir)? {
+    return Err(WaffleError::DirtyWorkingTree(FileName::new(dir)))
+  }
+
+  if git::tag_exists(dir, tag)? {
+    return Err(WaffleError::TagAlreadyExists(tag.to_owned()))
+  }
+
+  git::create_tag(dir, tag, message)?;
+  git::get_commit(dir, tag)
+}
+
+
+fn get_current_version(toml_file: &Path, console: &Console) -> ResultW<TomlData> {
+  let toml_data = wtoml::get_current_version(toml_file)?;
+  if toml_data.toml_file != toml_file {
+    console.info(s!("Using workspace toml file: {}", toml_data.toml_file.to_string_lossy()));
   }
+
+  Ok(toml_data)
+}
+
+
+fn bump_workspace(toml_file: &Path, bump_type: BumpType, no_diff: bool, dry_run: bool, console: &Console) -> ResultW<Output> {
+  let workspace_toml_file = wtoml::get_workspace_toml_file(toml_file)?;
+  let member_toml_files = wtoml::get_workspace_members(&workspace_toml_file)?;
+  debug!("Workspace root {} has members: {member_toml_files:?}", workspace_toml_file.to_string_lossy());
+
+  let mut bumps: Vec<VersionBump> = vec![];
+
+  // Members that inherit their version resolve to the workspace root, which is only bumped once.
+  for member_toml_file in std::iter::once(&workspace_toml_file).chain(member_toml_files.iter()) {
+    let toml_data = match wtoml::get_current_version(member_toml_file) {
+      Err(WaffleError::NoPackageVersion(_)) if member_toml_file == &workspace_toml_file => continue,
+      result => result?,
+    };
+
+    if bumps.iter().any(|bump| bump.toml_file == toml_data.toml_file) {
+      continue
+    }
+
+    let TomlData { package, toml_file, .. } = toml_data;
+    let package_name = package.name.clone();
+    let validated_current_version: ValidatedPackage = package.try_into()?;
+    let next_version = validated_current_version.bump_version(bump_type.clone())?;
+
+    let bump =
+      VersionBump {
+        toml_file,
+        name: package_name,
+        old_version: validated_current_version,
+        new_version: next_version,
+      };
+
+    bumps.push(bump);
+  }
+
+  let changeset = version_changes(&bumps)?;
+  let files_changed = write_changes(&changeset, no_diff, dry_run, console)?;
+
+  Ok(Output::WorkspaceBump { bumps, files_changed, dry_run })
+}
+
+
+/// Updates the version in each toml file along with any workspace dependency requirements on, and Cargo.lock entries
+/// of, the updated crates.
+fn version_changes(version_updates: &[VersionBump]) -> ResultW<Changeset> {
+  let mut changeset = Changeset::default();
+
+  for VersionBump { toml_file, new_version, .. } in version_updates {
+    changeset.update_file(toml_file, |file, content| Ok(wtoml::update_toml(file, content, new_version.clone())?.to_string()))?;
+  }
+
+  let workspace_toml_file = match version_updates.first().map(|bump| wtoml::get_workspace_toml_file(&bump.toml_file)) {
+    Some(Ok(workspace_toml_file)) => Some(workspace_toml_file),
+    None | Some(Err(WaffleError::NotAWorkspace(_))) => None,
+    Some(Err(error)) => return Err(error),
+  };
+
+  let toml_files = match &workspace_toml_file {
+    Some(workspace_toml_file) => {
+      let mut toml_files = wtoml::get_workspace_members(workspace_toml_file)?;
+      toml_files.push(workspace_toml_file.clone());
+      toml_files
+    },
+
+    None => version_updates.iter().map(|bump| bump.toml_file.clone()).collect(),
+  };
+
+  let updated_crates = get_updated_crates(&toml_files, version_updates)?;
+  debug!("Updated crates: {:?}", updated_crates.iter().map(|c| &c.name).collect::<Vec<_>>());
+
+  if workspace_toml_file.is_some() {
+    for toml_file in &toml_files {
+      changeset.update_file(toml_file, |file, content| wtoml::update_dependency_requirements(file, content, &updated_crates))?;
+    }
+  }
+
+  // The lock file lives next to the workspace root, or the package if there is no workspace.
+  let lock_file =
+    workspace_toml_file
+      .as_ref()
+      .or(version_updates.first().map(|bump| &bump.toml_file))
+      .and_then(|toml_file| toml_file.parent())
+      .map(|dir| dir.join("Cargo.lock"));
+
+  if let Some(lock_file) = lock_file.filter(|file| file.is_file()) {
+    debug!("Updating lock file: {}", lock_file.to_string_lossy());
+    changeset.update_file(lock_file, |file, content| wtoml::update_lock_file(file, content, &updated_crates))?;
+  }
+
+  Ok(changeset)
+}
+
+
+/// Writes all the changes at once, returning the files that were changed. On a dry run nothing is written, but the
+/// diff and the files that would have been changed are still returned.
+fn write_changes(changeset: &Changeset, no_diff: bool, dry_run: bool, console: &Console) -> ResultW<Vec<PathBuf>> {
+  if dry_run {
+    debug!("Dry run, skipping writing {} changed file(s)", changeset.changes().len());
+  } else {
+    debug!("Writing {} changed file(s)", changeset.changes().len());
+    write_file_changes(changeset.changes())?;
+  }
+
+  if !no_diff {
+    for change in changeset.changes() {
+      console.info(s!("{}:", change.file.to_string_lossy()));
+      console.info(render_diff(&change.content, &change.new_content))
+    }
+  }
+
+  let changed_files =
+    changeset
+      .changes()
+      .iter()
+      .map(|change| change.file.clone())
+      .collect();
+
+  Ok(changed_files)
+}
+
+
+/// A crate is updated if the toml file its version comes from is updated.
+fn get_updated_crates(toml_files: &[PathBuf], version_updates: &[VersionBump]) -> ResultW<Vec<UpdatedCrate>> {
+  let canonical_version_updates: Vec<_> =
+    version_updates
+      .iter()
+      .filter_map(|bump| bump.toml_file.canonicalize().ok().map(|file| (file, &bump.old_version, &bump.new_version)))
+      .collect();
+
+  let mut updated_crates = vec![];
+  for toml_file in toml_files {
+    let TomlData { package, toml_file: version_toml_file } = match wtoml::get_current_version(toml_file) {
+      Err(WaffleError::NoPackageVersion(_)) => continue,
+      result => result?,
+    };
+
+    let version_update =
+      version_toml_file
+        .canonicalize()
+        .ok()
+        .and_then(|file| canonical_version_updates.iter().find(|(f, ..)| *f == file));
+
+    if let (Some(name), Some((_, current_version, next_version))) = (package.name, version_update) {
+      let updated_crate =
+        UpdatedCrate {
+          name,
+          current_version: (*current_version).clone(),
+          next_version: (*next_version).clone(),
+        };
+
+      updated_crates.push(updated_crate)
+    }
+  }
+
+  Ok(updated_crates)
 }
@@ -0,0 +1,232 @@
+use std::path::Path;
+use toml_edit::{DocumentMut, Formatted, Item, TableLike, Value};
+use log::debug;
+
+use crate::error::{FileName, ResultW, TomlContent, WaffleError};
+use super::{UpdatedCrate, ValidatedPackage};
+
+const DEPENDENCY_TABLES: [&str; 3] = ["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// Requirement operators whose requirement should follow the version of the dependency.
+/// Upper bounds (<, <=) and wildcards are left alone.
+const SUPPORTED_OPERATORS: [&str; 4] = [">=", "^", "~", "="];
+
+
+/// Rewrites the version requirement of every path dependency on one of the supplied crates, across
+/// [dependencies], [dev-dependencies], [build-dependencies], their target-specific variants and
+/// [workspace.dependencies].
+pub fn update_dependency_requirements<P: AsRef<Path>>(toml_file: P, toml_content: &str, crates: &[UpdatedCrate]) -> ResultW<String> {
+  let mut doc =
+    toml_content.parse::<DocumentMut>()
+    .map_err(|e| WaffleError::CouldConvertTomlContentToDocument(FileName::new(toml_file.as_ref()), TomlContent::new(toml_content), e.to_string()))?;
+
+  let mut dependency_tables: Vec<&mut dyn TableLike> = vec![];
+  for (key, item) in doc.iter_mut() {
+    match key.get() {
+      "target" =>
+        for (_, target) in item.as_table_like_mut().into_iter().flat_map(|t| t.iter_mut()) {
+          dependency_tables.extend(get_dependency_tables(target))
+        },
+
+      "workspace" =>
+        dependency_tables.extend(item.get_mut("dependencies").and_then(Item::as_table_like_mut)),
+
+      name if DEPENDENCY_TABLES.contains(&name) =>
+        dependency_tables.extend(item.as_table_like_mut()),
+
+      _ => (),
+    }
+  }
+
+  for table in dependency_tables {
+    for (key, dependency) in table.iter_mut() {
+      if let Some(dependency) = dependency.as_table_like_mut() {
+        update_dependency(key.get(), dependency, crates)
+      }
+    }
+  }
+
+  Ok(doc.to_string())
+}
+
+
+fn get_dependency_tables(target: &mut Item) -> Vec<&mut dyn TableLike> {
+  target
+    .as_table_like_mut()
+    .into_iter()
+    .flat_map(|t| t.iter_mut())
+    .filter(|(key, _)| DEPENDENCY_TABLES.contains(&key.get()))
+    .filter_map(|(_, item)| item.as_table_like_mut())
+    .collect()
+}
+
+
+fn update_dependency(key: &str, dependency: &mut dyn TableLike, crates: &[UpdatedCrate]) {
+  // A renamed dependency refers to its crate through `package`
+  let crate_name =
+    dependency
+      .get("package")
+      .and_then(Item::as_str)
+      .unwrap_or(key)
+      .to_owned();
+
+  let next_version = crates.iter().find(|c| c.name == crate_name).map(|c| &c.next_version);
+
+  if let (Some(next_version), true) = (next_version, dependency.contains_key("path")) {
+    if let Some(Item::Value(Value::String(requirement))) = dependency.get_mut("version") {
+      if let Some(next_requirement) = update_version_requirement(requirement.value(), next_version) {
+        debug!("Updating requirement on {crate_name} from: {} -> {next_requirement}", requirement.value());
+        let decor = requirement.decor().clone();
+        *requirement = Formatted::new(next_requirement);
+        *requirement.decor_mut() = decor;
+      }
+    }
+  }
+}
+
+
+/// Updates a version requirement to the next version, keeping its operator and the number of version components.
+/// eg. with a next version of 0.5.0: "0.4" -> "0.5", "=0.4.2" -> "=0.5.0", "~0.4" -> "~0.5"
+///
+/// Returns None for requirements that can't be updated, such as multiple comparators, wildcards or upper bounds.
+pub fn update_version_requirement(requirement: &str, next_version: &ValidatedPackage) -> Option<String> {
+  let trimmed = requirement.trim();
+  if trimmed.contains(',') || trimmed.contains('*') {
+    return None
+  }
+
+  let operator =
+    SUPPORTED_OPERATORS
+      .iter()
+      .find(|op| trimmed.starts_with(*op))
+      .copied()
+      .unwrap_or("");
+
+  let version = trimmed[operator.len()..].trim_start();
+  let operator_with_spacing = &trimmed[..trimmed.len() - version.len()];
+
+  if version.is_empty() || !version.starts_with(|c: char| c.is_ascii_digit()) {
+    return None
+  }
+
+  let components = version.split(['-', '+']).next().unwrap_or_default().split('.').count();
+  let next_requirement_version = match components {
+    1 if !next_version.is_pre_release() => next_version.major.to_string(),
+    2 if !next_version.is_pre_release() => format!("{}.{}", next_version.major, next_version.minor),
+    _ => next_version.to_string(),
+  };
+
+  Some(format!("{operator_with_spacing}{next_requirement_version}"))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn updated_crate(name: &str, next_version: &str) -> UpdatedCrate {
+      UpdatedCrate {
+        name: name.to_owned(),
+        current_version: ValidatedPackage::new("0.4.2"),
+        next_version: ValidatedPackage::new(next_version),
+      }
+    }
+
+    #[test]
+    fn keeps_operator_and_precision() {
+      let next_version = ValidatedPackage::new("0.5.0");
+
+      assert_eq!(update_version_requirement("0.4", &next_version).as_deref(), Some("0.5"));
+      assert_eq!(update_version_requirement("0.4.2", &next_version).as_deref(), Some("0.5.0"));
+      assert_eq!(update_version_requirement("0", &next_version).as_deref(), Some("0"));
+      assert_eq!(update_version_requirement("^0.4.2", &next_version).as_deref(), Some("^0.5.0"));
+      assert_eq!(update_version_requirement("~0.4", &next_version).as_deref(), Some("~0.5"));
+      assert_eq!(update_version_requirement("=0.4.2", &next_version).as_deref(), Some("=0.5.0"));
+      assert_eq!(update_version_requirement(">= 0.4.2", &next_version).as_deref(), Some(">= 0.5.0"));
+    }
+
+    #[test]
+    fn uses_full_version_for_pre_release() {
+      assert_eq!(update_version_requirement("0.4", &ValidatedPackage::new("0.5.0-rc.1")).as_deref(), Some("0.5.0-rc.1"));
+    }
+
+    #[test]
+    fn skips_unsupported_requirements() {
+      let next_version = ValidatedPackage::new("0.5.0");
+
+      assert_eq!(update_version_requirement(">=0.4, <0.5", &next_version), None);
+      assert_eq!(update_version_requirement("0.4.*", &next_version), None);
+      assert_eq!(update_version_requirement("*", &next_version), None);
+      assert_eq!(update_version_requirement("<0.5", &next_version), None);
+    }
+
+    #[test]
+    fn updates_path_dependencies_in_all_tables() {
+      let content = r#"
+[package]
+name = "b"
+version = "1.0.0"
+
+[dependencies]
+a = { path = "../a", version = "0.4" } # keep me
+serde = "1"
+
+[dev-dependencies.a]
+path = "../a"
+version = "=0.4.2"
+
+[build-dependencies]
+renamed = { package = "a", path = "../a", version = "~0.4.2" }
+
+[target.'cfg(unix)'.dependencies]
+a = { path = "../a", version = "^0.4" }
+
+[workspace.dependencies]
+a = { path = "a", version = "0.4.2" }
+"#;
+
+      let expected_content = r#"
+[package]
+name = "b"
+version = "1.0.0"
+
+[dependencies]
+a = { path = "../a", version = "0.5" } # keep me
+serde = "1"
+
+[dev-dependencies.a]
+path = "../a"
+version = "=0.5.0"
+
+[build-dependencies]
+renamed = { package = "a", path = "../a", version = "~0.5.0" }
+
+[target.'cfg(unix)'.dependencies]
+a = { path = "../a", version = "^0.5" }
+
+[workspace.dependencies]
+a = { path = "a", version = "0.5.0" }
+"#;
+
+      let crates = [updated_crate("a", "0.5.0")];
+      let updated_content = update_dependency_requirements("Cargo.toml", content, &crates).unwrap();
+
+      assert_eq!(updated_content, expected_content)
+    }
+
+    #[test]
+    fn skips_registry_and_other_dependencies() {
+      let content = r#"
+[dependencies]
+a = "0.4"
+b = { path = "../b", version = "0.4" }
+c = { workspace = true }
+"#;
+
+      let crates = [updated_crate("a", "0.5.0"), updated_crate("c", "0.5.0")];
+      let updated_content = update_dependency_requirements("Cargo.toml", content, &crates).unwrap();
+
+      assert_eq!(updated_content, content)
+    }
+}
@@ -0,0 +1,91 @@
+use std::path::Path;
+use toml_edit::{DocumentMut, Formatted, Item, Value};
+use log::debug;
+
+use crate::error::{FileName, ResultW, TomlContent, WaffleError};
+use super::UpdatedCrate;
+
+
+/// Updates the version of the [[package]] entries of the supplied crates in a Cargo.lock file. Only local packages,
+/// which have no `source`, with a matching name and the current version of the crate are updated.
+pub fn update_lock_file<P: AsRef<Path>>(lock_file: P, lock_content: &str, crates: &[UpdatedCrate]) -> ResultW<String> {
+  let mut doc =
+    lock_content.parse::<DocumentMut>()
+    .map_err(|e| WaffleError::CouldConvertTomlContentToDocument(FileName::new(lock_file.as_ref()), TomlContent::new(lock_content), e.to_string()))?;
+
+  let packages = doc.get_mut("package").and_then(Item::as_array_of_tables_mut);
+
+  for package in packages.into_iter().flat_map(|p| p.iter_mut()) {
+    let name = package.get("name").and_then(Item::as_str).unwrap_or_default().to_owned();
+    let is_local = !package.contains_key("source");
+
+    if let Some(Item::Value(Value::String(version))) = package.get_mut("version") {
+      let updated_crate =
+        crates
+          .iter()
+          .find(|c| is_local && c.name == name && c.current_version.to_string() == *version.value());
+
+      if let Some(updated_crate) = updated_crate {
+        debug!("Updating Cargo.lock entry of {name} from: {} -> {}", version.value(), updated_crate.next_version);
+        let decor = version.decor().clone();
+        *version = Formatted::new(updated_crate.next_version.to_string());
+        *version.decor_mut() = decor;
+      }
+    }
+  }
+
+  Ok(doc.to_string())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wtoml::ValidatedPackage;
+    use pretty_assertions::assert_eq;
+
+    const LOCK_CONTENT: &str = r#"# This file is automatically @generated by Cargo.
+# It is not intended for manual editing.
+version = 3
+
+[[package]]
+name = "ansi_term"
+version = "0.12.1"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "d52a9bb7ec0cf484c551830a7ce27bd20d67eac647e1befb56b0be4ee39a55d2"
+
+[[package]]
+name = "waffle"
+version = "0.12.1"
+dependencies = [
+ "ansi_term",
+]
+
+[[package]]
+name = "waffle"
+version = "0.12.1"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#;
+
+    #[test]
+    fn updates_only_local_package_version() {
+      let updated_crate =
+        UpdatedCrate {
+          name: "waffle".to_owned(),
+          current_version: ValidatedPackage::new("0.12.1"),
+          next_version: ValidatedPackage::new("0.13.0"),
+        };
+
+      let updated_content = update_lock_file("Cargo.lock", LOCK_CONTENT, &[updated_crate]).unwrap();
+      let expected_content = LOCK_CONTENT.replacen("name = \"waffle\"\nversion = \"0.12.1\"", "name = \"waffle\"\nversion = \"0.13.0\"", 1);
+
+      assert_eq!(updated_content, expected_content)
+    }
+
+    #[test]
+    fn leaves_lock_file_without_updated_crates_unchanged() {
+      let updated_content = update_lock_file("Cargo.lock", LOCK_CONTENT, &[]).unwrap();
+
+      assert_eq!(updated_content, LOCK_CONTENT)
+    }
+}
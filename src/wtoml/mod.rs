@@ -1,6 +1,12 @@
 mod toml_serde;
 mod toml_funcs;
+mod workspace_funcs;
+mod dependency_funcs;
+mod lock_funcs;
 
-pub use toml_serde::{CargoToml, Package, ValidatedPackage, TomlData};
-pub use toml_funcs::{get_current_version, get_toml_file, write_updated_version};
+pub use toml_serde::{CargoPackage, CargoToml, Metadata, Package, PackageVersion, PreReleaseIdentifier, ValidatedPackage, TomlData, UpdatedCrate, VersionSource, WaffleConfig, Workspace, WorkspacePackage};
+pub use toml_funcs::{get_config, get_current_version, get_toml_file, parse_cargo_toml, update_toml};
+pub use dependency_funcs::update_dependency_requirements;
+pub use lock_funcs::update_lock_file;
+pub use workspace_funcs::{get_workspace_toml_file, get_workspace_member, get_workspace_members};
 
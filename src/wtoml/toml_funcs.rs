@@ -1,63 +1,260 @@
 use std::fs;
 use std::path::{Path, PathBuf};
-use toml_edit::{value, DocumentMut};
+use toml_edit::{value, DocumentMut, Item};
+use log::{debug, trace};
 
 use crate::error::{FileName, ResultW, TomlContent, WaffleError};
-use super::{CargoToml, TomlData, ValidatedPackage};
+use super::{CargoToml, Package, TomlData, ValidatedPackage, VersionSource, WaffleConfig};
 
+/// Reads the toml file and returns its package name and current version.
 pub fn get_current_version(file_name: &Path) -> ResultW<TomlData> {
   let toml_content = load_toml_file(file_name)?;
-  let toml_struct: CargoToml =
-    toml
-      ::from_str(&toml_content)
-      .map_err(|e| WaffleError::CouldParseTomlFile(FileName::new(file_name), e.to_string()))?;
+  let toml_struct = parse_cargo_toml(file_name, &toml_content)?;
+
+  let name = toml_struct.package_name();
+  let version_source = toml_struct.version_source();
+  debug!("Version source of {}: {version_source:?}", file_name.to_string_lossy());
+
+  match version_source {
+    VersionSource::Package(version) | VersionSource::WorkspacePackage(version) => {
+      let toml_data =
+        TomlData {
+          package: Package { name, version },
+          toml_file: file_name.to_owned(),
+        };
+
+      Ok(toml_data)
+    },
+
+    VersionSource::Inherited => get_inherited_version(file_name, name),
+
+    VersionSource::NotFound => Err(WaffleError::NoPackageVersion(FileName::new(file_name))),
+  }
+}
+
+
+fn get_inherited_version(file_name: &Path, name: Option<String>) -> ResultW<TomlData> {
+  let workspace_toml_file =
+    find_workspace_root(file_name)?
+      .ok_or_else(|| WaffleError::WorkspaceRootNotFound(FileName::new(file_name)))?;
+
+  debug!("Resolving inherited version of {} from workspace root: {}", file_name.to_string_lossy(), workspace_toml_file.to_string_lossy());
+
+  let toml_content = load_toml_file(&workspace_toml_file)?;
+  let version =
+    parse_cargo_toml(&workspace_toml_file, &toml_content)?
+      .workspace_version()
+      .ok_or_else(|| WaffleError::NoPackageVersion(FileName::new(&workspace_toml_file)))?;
 
   let toml_data =
     TomlData {
-      package: toml_struct.package,
-      content: toml_content
+      package: Package { name, version },
+      toml_file: workspace_toml_file,
     };
 
   Ok(toml_data)
 }
 
 
+/// Reads the Waffle config of a toml file, falling back to the config of its workspace root.
+pub fn get_config(file_name: &Path) -> ResultW<WaffleConfig> {
+  let toml_struct = parse_cargo_toml(file_name, &load_toml_file(file_name)?)?;
+  let config = toml_struct.package_config().or(toml_struct.workspace_config());
+
+  match find_workspace_root(file_name)? {
+    Some(workspace_toml_file) if toml_struct.workspace.is_none() => {
+      let workspace_toml_struct = parse_cargo_toml(&workspace_toml_file, &load_toml_file(&workspace_toml_file)?)?;
+      Ok(config.or(workspace_toml_struct.workspace_config()))
+    },
+
+    _ => Ok(config),
+  }
+}
+
+
+/// Searches the directories above the supplied toml file for a Cargo.toml with a [workspace] table.
+pub fn find_workspace_root(file_name: &Path) -> ResultW<Option<PathBuf>> {
+  let toml_file =
+    file_name
+      .canonicalize()
+      .map_err(|e| WaffleError::CouldNotReadTomlFile(FileName::new(file_name), e.to_string()))?;
+
+  for dir in toml_file.ancestors().skip(1) {
+    let candidate = dir.join("Cargo.toml");
+    if candidate != toml_file && candidate.is_file() {
+      trace!("Looking for a workspace root at: {}", candidate.to_string_lossy());
+      let content = load_toml_file(&candidate)?;
+      if parse_cargo_toml(&candidate, &content)?.workspace.is_some() {
+        debug!("Found workspace root of {}: {}", toml_file.to_string_lossy(), candidate.to_string_lossy());
+        return Ok(Some(candidate))
+      }
+    }
+  }
+
+  debug!("No workspace root found above: {}", toml_file.to_string_lossy());
+  Ok(None)
+}
+
+
+/// Parses the content of a Cargo.toml file. The file name is only used for error messages.
+pub fn parse_cargo_toml(file_name: &Path, toml_content: &str) -> ResultW<CargoToml> {
+  let cargo_toml: CargoToml =
+    toml
+      ::from_str(toml_content)
+      .map_err(|e| WaffleError::CouldParseTomlFile(FileName::new(file_name), e.to_string()))?;
+
+  trace!("Parsed toml file {}: {cargo_toml:?}", file_name.to_string_lossy());
+  Ok(cargo_toml)
+}
+
+
 pub fn load_toml_file(file_name: &Path) -> ResultW<String> {
+  trace!("Reading toml file: {}", file_name.to_string_lossy());
   fs
     ::read_to_string(file_name)
     .map_err(|e| WaffleError::CouldNotReadTomlFile(FileName::new(file_name), e.to_string()))
 }
 
 
-pub fn get_toml_file(toml_file_arg: Option<String>) -> PathBuf {
-  let default_toml_file = PathBuf::from("./Cargo.toml");
-  toml_file_arg
-    .map_or_else(|| default_toml_file, PathBuf::from)
+/// The supplied toml file or, like Cargo, the nearest Cargo.toml in the current directory or its ancestors.
+pub fn get_toml_file(toml_file_arg: Option<String>) -> ResultW<PathBuf> {
+  match toml_file_arg {
+    Some(toml_file) => Ok(PathBuf::from(toml_file)),
+    None => {
+      let current_dir =
+        std::env::current_dir()
+          .map_err(|e| WaffleError::CouldNotReadTomlFile(FileName::new(Path::new("Cargo.toml")), e.to_string()))?;
+
+      find_toml_file(&current_dir)
+    },
+  }
 }
 
 
-pub fn write_updated_version<P: AsRef<Path>>(toml_file: P, toml_content: &str, next_version: ValidatedPackage) -> ResultW<String> {
+/// Searches the directory and its ancestors for a Cargo.toml. The nearest package manifest is preferred, so a workspace
+/// root is only used if there is no package manifest on the way to it. Like Cargo, the search stops at the workspace
+/// root, so manifests above it are never used.
+pub fn find_toml_file(dir: &Path) -> ResultW<PathBuf> {
+  let mut nearest_toml_file = None;
+
+  for ancestor in dir.ancestors() {
+    let candidate = ancestor.join("Cargo.toml");
+    if candidate.is_file() {
+      let content = load_toml_file(&candidate)?;
+      let cargo_toml = parse_cargo_toml(&candidate, &content)?;
 
-  let updated_toml = update_toml(&toml_file, toml_content, next_version)?;
-  let new_toml_content = updated_toml.to_string();
-  write_toml_file(toml_file, &new_toml_content)?;
+      if cargo_toml.package.is_some() {
+        debug!("Found package toml file: {}", candidate.to_string_lossy());
+        return Ok(candidate)
+      }
 
-  Ok(new_toml_content)
+      if cargo_toml.workspace.is_some() {
+        debug!("Found workspace toml file: {}", candidate.to_string_lossy());
+        return Ok(candidate)
+      }
+
+      trace!("Found toml file without a package: {}", candidate.to_string_lossy());
+      nearest_toml_file.get_or_insert(candidate);
+    }
+  }
+
+  nearest_toml_file.ok_or_else(|| WaffleError::TomlFileNotFound(FileName::new(dir)))
 }
 
 
+/// Sets the version in the toml content without writing it to disk, keeping its formatting intact.
+/// A version inherited from the workspace is updated in workspace.package.version instead.
 pub fn update_toml<P: AsRef<Path>>(toml_file: P, toml_content: &str, next_version: ValidatedPackage) -> ResultW<DocumentMut> {
   let mut doc =
     toml_content.parse::<DocumentMut>()
     .map_err(|e| WaffleError::CouldConvertTomlContentToDocument(FileName::new(toml_file.as_ref()), TomlContent::new(toml_content), e.to_string()))?;
 
-  doc["package"]["version"] = value(next_version.clone());
+  // Update package.version unless it's inherited from the workspace, in which case update workspace.package.version
+  let package_version = doc.get("package").and_then(|p| p.get("version"));
+  if package_version.is_some_and(|v| v.is_str()) {
+    debug!("Updating package.version of {} to: {next_version}", toml_file.as_ref().to_string_lossy());
+    set_version(&mut doc["package"]["version"], next_version);
+  } else {
+    debug!("Updating workspace.package.version of {} to: {next_version}", toml_file.as_ref().to_string_lossy());
+    set_version(&mut doc["workspace"]["package"]["version"], next_version);
+  }
 
   Ok(doc)
 }
 
 
-fn write_toml_file<P: AsRef<Path>>(toml_file: P, content: &str) -> ResultW<()> {
-  std::fs::write(toml_file.as_ref(), content)
-    .map_err(|e| WaffleError::CouldNotUpdateTomlFile(FileName::new(toml_file.as_ref()), TomlContent::new(content), e.to_string()))
+/// Replaces the version, keeping the decor of the existing one, such as its spacing and a trailing comment.
+fn set_version(version: &mut Item, next_version: ValidatedPackage) {
+  let decor = version.as_value().map(|v| v.decor().clone());
+  *version = value(next_version);
+
+  if let (Some(decor), Some(version)) = (decor, version.as_value_mut()) {
+    *version.decor_mut() = decor;
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+    use std::fs;
+
+    #[test]
+    fn finds_nearest_package_toml_file() {
+      let working_dir = tempdir().unwrap();
+      let root = working_dir.path();
+      let src_dir = root.join("app").join("src");
+
+      fs::create_dir_all(&src_dir).unwrap();
+      fs::write(root.join("Cargo.toml"), "[workspace]\nmembers = [\"app\"]\n").unwrap();
+      fs::write(root.join("app").join("Cargo.toml"), "[package]\nversion = \"1.0.0\"\n").unwrap();
+
+      assert_eq!(find_toml_file(&src_dir).unwrap(), root.join("app").join("Cargo.toml"));
+      assert_eq!(find_toml_file(&root.join("app")).unwrap(), root.join("app").join("Cargo.toml"))
+    }
+
+    #[test]
+    fn finds_workspace_toml_file_without_package() {
+      let working_dir = tempdir().unwrap();
+      let root = working_dir.path();
+      let docs_dir = root.join("docs");
+
+      fs::create_dir_all(&docs_dir).unwrap();
+      fs::write(root.join("Cargo.toml"), "[workspace]\nmembers = [\"app\"]\n").unwrap();
+
+      assert_eq!(find_toml_file(&docs_dir).unwrap(), root.join("Cargo.toml"))
+    }
+
+    #[test]
+    fn update_toml_keeps_version_decor() {
+      let content = "[package]\nname = \"sample\"\nversion=\"1.2.3\"   # keep in sync with docs\n\n[workspace.package]\nversion  =  \"1.0.0\" # shared\n";
+      let next_version: ValidatedPackage = "1.2.4".parse().unwrap();
+
+      let updated_content = update_toml("Cargo.toml", content, next_version).unwrap().to_string();
+
+      assert_eq!(updated_content, content.replace("1.2.3", "1.2.4"));
+
+      let inherited_content = "[package]\nversion.workspace = true\n\n[workspace.package]\nversion  =  \"1.0.0\" # shared\n";
+      let next_version: ValidatedPackage = "1.1.0".parse().unwrap();
+
+      let updated_content = update_toml("Cargo.toml", inherited_content, next_version).unwrap().to_string();
+
+      assert_eq!(updated_content, inherited_content.replace("1.0.0", "1.1.0"))
+    }
+
+    #[test]
+    fn stops_at_workspace_root() {
+      let working_dir = tempdir().unwrap();
+      let outer = working_dir.path();
+      let inner = outer.join("inner");
+      let docs_dir = inner.join("docs");
+
+      fs::create_dir_all(&docs_dir).unwrap();
+      fs::write(outer.join("Cargo.toml"), "[package]\nversion = \"9.9.9\"\n").unwrap();
+      fs::write(inner.join("Cargo.toml"), "[workspace]\nmembers = []\n\n[workspace.package]\nversion = \"0.1.0\"\n").unwrap();
+
+      assert_eq!(find_toml_file(&docs_dir).unwrap(), inner.join("Cargo.toml"))
+    }
 }
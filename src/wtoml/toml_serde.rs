@@ -1,27 +1,159 @@
 use toml_edit::Formatted;
 use toml_edit::Value;
 
+use std::cmp::Ordering;
+use std::path::PathBuf;
 use std::str::FromStr;
 use std::fmt;
-use std::format as s;
 
-use crate::error::WaffleError;
-use crate::args::BumpType;
+use crate::error::{ResultW, WaffleError};
+use crate::args::{BumpType, PreReleaseChannel};
 
 
 pub struct TomlData {
   pub package: Package,
-  pub content: String
+  /// The toml file the version was read from. For a package that inherits its version, this is the workspace root.
+  pub toml_file: PathBuf,
 }
 
 
-#[derive(serde::Deserialize)]
+/// A crate whose version is being updated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpdatedCrate {
+  pub name: String,
+  pub current_version: ValidatedPackage,
+  pub next_version: ValidatedPackage,
+}
+
+
+/// The parts of a Cargo.toml file that Waffle reads.
+#[derive(Debug, serde::Deserialize)]
 pub struct CargoToml {
-  pub package: Package
+  pub package: Option<CargoPackage>,
+  pub workspace: Option<Workspace>,
+}
+
+/// The `[package]` table.
+#[derive(Debug, serde::Deserialize)]
+pub struct CargoPackage {
+  pub name: Option<String>,
+  pub version: Option<PackageVersion>,
+  pub metadata: Option<Metadata>,
+}
+
+/// The `metadata` table of `[package]` or `[workspace]`.
+#[derive(Debug, serde::Deserialize)]
+pub struct Metadata {
+  pub waffle: Option<WaffleConfig>
+}
+
+/// Waffle configuration from [package.metadata.waffle] or [workspace.metadata.waffle]
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct WaffleConfig {
+  pub tag_template: Option<String>,
+  pub commit_message: Option<String>,
 }
 
+impl WaffleConfig {
+
+  /// Uses the values from this config, falling back to the other config for any that are missing.
+  pub fn or(self, other: WaffleConfig) -> WaffleConfig {
+    WaffleConfig {
+      tag_template: self.tag_template.or(other.tag_template),
+      commit_message: self.commit_message.or(other.commit_message),
+    }
+  }
+}
+
+/// package.version is either a version or inherited from the workspace with `version.workspace = true`.
+#[derive(Debug, serde::Deserialize)]
+#[serde(untagged)]
+pub enum PackageVersion {
+  Version(String),
+  Inherited { workspace: bool },
+}
+
+/// The `[workspace]` table.
+#[derive(Debug, serde::Deserialize)]
+pub struct Workspace {
+  pub members: Option<Vec<String>>,
+  pub exclude: Option<Vec<String>>,
+  pub package: Option<WorkspacePackage>,
+  pub metadata: Option<Metadata>,
+}
+
+/// The `[workspace.package]` table, which members can inherit their version from.
+#[derive(Debug, serde::Deserialize)]
+pub struct WorkspacePackage {
+  pub version: Option<String>
+}
+
+/// Where the version of a toml file is defined.
+#[derive(Debug)]
+pub enum VersionSource {
+  /// package.version
+  Package(String),
+  /// workspace.package.version
+  WorkspacePackage(String),
+  /// package.version.workspace = true
+  Inherited,
+  NotFound,
+}
+
+impl CargoToml {
+
+  pub fn package_name(&self) -> Option<String> {
+    self.package.as_ref().and_then(|p| p.name.clone())
+  }
+
+  pub fn package_config(&self) -> WaffleConfig {
+    self
+      .package
+      .as_ref()
+      .and_then(|p| p.metadata.as_ref())
+      .and_then(|m| m.waffle.clone())
+      .unwrap_or_default()
+  }
+
+  pub fn workspace_config(&self) -> WaffleConfig {
+    self
+      .workspace
+      .as_ref()
+      .and_then(|w| w.metadata.as_ref())
+      .and_then(|m| m.waffle.clone())
+      .unwrap_or_default()
+  }
+
+  pub fn version_source(&self) -> VersionSource {
+    let package_version = self.package.as_ref().and_then(|p| p.version.as_ref());
+
+    match (package_version, self.workspace_version()) {
+      (Some(PackageVersion::Version(version)), _) => VersionSource::Package(version.to_owned()),
+      // The workspace root can inherit from its own workspace.package
+      (Some(PackageVersion::Inherited { workspace: true }), Some(version)) => VersionSource::WorkspacePackage(version),
+      (Some(PackageVersion::Inherited { workspace: true }), None) => VersionSource::Inherited,
+      _ =>
+        self
+          .workspace_version()
+          .map_or(VersionSource::NotFound, VersionSource::WorkspacePackage)
+    }
+  }
+
+  pub fn workspace_version(&self) -> Option<String> {
+    self
+      .workspace
+      .as_ref()
+      .and_then(|w| w.package.as_ref())
+      .and_then(|p| p.version.clone())
+  }
+}
+
+/// The unvalidated package section of a Cargo.toml file. Convert it into a [ValidatedPackage] to work with its version.
 #[derive(Debug, PartialEq, serde::Deserialize, Clone)]
 pub struct Package {
+  /// A workspace root without a `[package]` has no name
+  pub name: Option<String>,
   pub version: String
 }
 
@@ -30,6 +162,7 @@ impl Package {
   #[cfg(test)]
   fn new(version: &str) -> Self {
     Self {
+      name: None,
       version: version.to_owned()
     }
   }
@@ -43,98 +176,337 @@ impl fmt::Display for Package {
 }
 
 
-#[derive(Debug, Clone, PartialEq)]
+/// A pre-release identifier. Numeric identifiers have lower precedence than alphanumeric ones and are compared
+/// numerically, while alphanumeric identifiers are compared lexically in ASCII sort order.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PreReleaseIdentifier {
+  Numeric(u64),
+  AlphaNumeric(String),
+}
+
+impl fmt::Display for PreReleaseIdentifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+      match self {
+        PreReleaseIdentifier::Numeric(number) => write!(f, "{number}"),
+        PreReleaseIdentifier::AlphaNumeric(value) => write!(f, "{value}"),
+      }
+    }
+}
+
+
+/// A Semantic Version (2.0) of the form: `major.minor.patch[-pre-release][+build]`
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ValidatedPackage {
-  pub major: u16,
-  pub minor: u16,
-  pub patch: u16,
+  pub major: u64,
+  pub minor: u64,
+  pub patch: u64,
+  pub pre_release: Vec<PreReleaseIdentifier>,
+  pub build: Vec<String>,
 }
 
 impl fmt::Display for ValidatedPackage {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+
+        if !self.pre_release.is_empty() {
+          let pre_release: Vec<_> = self.pre_release.iter().map(|id| id.to_string()).collect();
+          write!(f, "-{}", pre_release.join("."))?;
+        }
+
+        if !self.build.is_empty() {
+          write!(f, "+{}", self.build.join("."))?;
+        }
+
+        Ok(())
     }
 }
 
+/// Serialised as the version string.
+impl serde::Serialize for ValidatedPackage {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(self)
+  }
+}
+
 impl ValidatedPackage {
 
   #[cfg(test)]
-  fn new(version: &str) -> Self {
-    let parts: Vec<_> =
-      version
-        .splitn(3, '.')
-        .map(|num| u16::from_str(num).unwrap())
-        .collect();
+  pub(crate) fn new(version: &str) -> Self {
+    Package::new(version).try_into().unwrap()
+  }
 
-    let (major, minor, patch) = (parts[0], parts[1], parts[2]);
-    Self {
+  /// Whether this version has a pre-release, such as 1.2.3-rc.1.
+  pub fn is_pre_release(&self) -> bool {
+    !self.pre_release.is_empty()
+  }
+
+  /// Compares versions by SemVer precedence, which ignores build metadata.
+  pub fn cmp_precedence(&self, other: &Self) -> Ordering {
+    (self.major, self.minor, self.patch)
+      .cmp(&(other.major, other.minor, other.patch))
+      .then_with(|| {
+        // A version without a pre-release has higher precedence than one with a pre-release.
+        match (self.pre_release.is_empty(), other.pre_release.is_empty()) {
+          (true, true) => Ordering::Equal,
+          (true, false) => Ordering::Greater,
+          (false, true) => Ordering::Less,
+          (false, false) => self.pre_release.cmp(&other.pre_release),
+        }
+      })
+  }
+
+  /// Major, Minor and Patch bumps always produce a release version; any pre-release and build metadata are dropped.
+  /// Pre-release bumps produce a pre-release on the supplied channel.
+  /// Fails with VersionOverflow if the bumped component would not fit in a u64.
+  pub fn bump_version(&self, bump_type: BumpType) -> ResultW<ValidatedPackage> {
+    let increment = |component: u64| component.checked_add(1).ok_or_else(|| WaffleError::VersionOverflow(self.clone(), bump_type.clone()));
+
+    let next_version = match bump_type {
+      BumpType::Major => self.release(increment(self.major)?, 0, 0),
+      BumpType::Minor => self.release(self.major, increment(self.minor)?, 0),
+      BumpType::Patch => self.release(self.major, self.minor, increment(self.patch)?),
+      BumpType::PreMajor(channel) => self.release(increment(self.major)?, 0, 0).with_pre_release(channel, 1),
+      BumpType::PreMinor(channel) => self.release(self.major, increment(self.minor)?, 0).with_pre_release(channel, 1),
+      BumpType::PrePatch(channel) => self.release(self.major, self.minor, increment(self.patch)?).with_pre_release(channel, 1),
+      BumpType::PreRelease(channel) => self.bump_pre_release(channel)?,
+      BumpType::Release if self.is_pre_release() => self.release(self.major, self.minor, self.patch),
+      BumpType::Release => return Err(WaffleError::AlreadyReleased(self.clone())),
+    };
+
+    Ok(next_version)
+  }
+
+  fn bump_pre_release(&self, channel: PreReleaseChannel) -> ResultW<ValidatedPackage> {
+    if !self.is_pre_release() {
+      return self.bump_version(BumpType::PrePatch(channel))
+    }
+
+    let base_version = self.release(self.major, self.minor, self.patch);
+    let next_version = match &self.pre_release[..] {
+      [PreReleaseIdentifier::AlphaNumeric(current_channel), PreReleaseIdentifier::Numeric(number)] if *current_channel == channel.to_string() =>
+        base_version.with_pre_release(channel, number.checked_add(1).ok_or_else(|| WaffleError::VersionOverflow(self.clone(), BumpType::PreRelease(channel)))?),
+      _ =>
+        base_version.with_pre_release(channel, 1),
+    };
+
+    if next_version.cmp_precedence(self) == Ordering::Greater {
+      Ok(next_version)
+    } else {
+      Err(WaffleError::PreReleaseChannelDowngrade(self.clone(), channel))
+    }
+  }
+
+  fn release(&self, major: u64, minor: u64, patch: u64) -> ValidatedPackage {
+    ValidatedPackage {
       major,
       minor,
-      patch
+      patch,
+      pre_release: vec![],
+      build: vec![],
     }
   }
 
-  pub fn bump_version(&self, bump_type: BumpType) -> ValidatedPackage {
-    let (next_major, next_minor, next_patch) = match bump_type {
-      BumpType::Major => (self.major + 1, 0_u16, 0_u16),
-      BumpType::Minor => (self.major, self.minor + 1, 0_u16),
-      BumpType::Patch => (self.major, self.minor, self.patch + 1),
-    };
-
+  fn with_pre_release(self, channel: PreReleaseChannel, number: u64) -> ValidatedPackage {
     ValidatedPackage {
-      major: next_major,
-      minor: next_minor,
-      patch: next_patch,
+      pre_release: vec![PreReleaseIdentifier::AlphaNumeric(channel.to_string()), PreReleaseIdentifier::Numeric(number)],
+      ..self
     }
   }
 }
 
+impl PartialOrd for ValidatedPackage {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+      Some(self.cmp(other))
+    }
+}
+
+// Build metadata is only used as a tie-breaker so that Ord stays consistent with Eq.
+// Use cmp_precedence for SemVer precedence.
+impl Ord for ValidatedPackage {
+    fn cmp(&self, other: &Self) -> Ordering {
+      self
+        .cmp_precedence(other)
+        .then_with(|| self.build.cmp(&other.build))
+    }
+}
+
 impl TryFrom<Package> for ValidatedPackage {
     type Error = WaffleError;
 
     fn try_from(package: Package) -> Result<Self, Self::Error> {
+      let version = package.version.as_str();
 
-      let u16_parts =
-        package
-          .clone()
-          .version
-          .split('.')
-          .map(|v| u16::from_str(v)
-          .map_err(|_| WaffleError::NonNumericVersions(package.clone())))
-          .collect::<Result<Vec<u16>, Self::Error>>()?;
-
-      match u16_parts[..] {
-        [major, minor, patch] => {
-          let validated =
-            ValidatedPackage {
-              major,
-              minor,
-              patch
-            };
+      let (version, build) = match version.split_once('+') {
+        Some((version, build)) => (version, Some(build)),
+        None => (version, None),
+      };
+
+      let (version, pre_release) = match version.split_once('-') {
+        Some((version, pre_release)) => (version, Some(pre_release)),
+        None => (version, None),
+      };
+
+      let core_parts: Vec<&str> = version.split('.').collect();
+
+      let numeric_parts =
+        core_parts
+          .iter()
+          .map(|v| parse_numeric_identifier(v).ok_or_else(|| WaffleError::NonNumericVersions(package.clone())))
+          .collect::<Result<Vec<u64>, Self::Error>>()?;
+
+      let (major, minor, patch) = match numeric_parts[..] {
+        [major, minor, patch] => (major, minor, patch),
+        _ => return Err(WaffleError::NotSemver(package)),
+      };
+
+      if core_parts.iter().any(|v| has_leading_zero(v)) {
+        return Err(WaffleError::LeadingZeroInVersion(package))
+      }
+
+      let pre_release =
+        pre_release
+          .map(|pre| parse_pre_release(pre).ok_or_else(|| WaffleError::InvalidPreRelease(package.clone())))
+          .transpose()?
+          .unwrap_or_default();
+
+      let build =
+        build
+          .map(|build| parse_build(build).ok_or_else(|| WaffleError::InvalidBuildMetadata(package.clone())))
+          .transpose()?
+          .unwrap_or_default();
+
+      let validated =
+        ValidatedPackage {
+          major,
+          minor,
+          patch,
+          pre_release,
+          build
+        };
+
+      Ok(validated)
+    }
+}
+
+fn parse_numeric_identifier<T: FromStr>(value: &str) -> Option<T> {
+  // FromStr for numbers accepts a leading '+', which SemVer does not.
+  if !value.is_empty() && value.chars().all(|c| c.is_ascii_digit()) {
+    T::from_str(value).ok()
+  } else {
+    None
+  }
+}
+
+fn has_leading_zero(value: &str) -> bool {
+  value.len() > 1 && value.starts_with('0')
+}
 
-            Ok(validated)
-        },
-        _ => Err(WaffleError::NotSemver(package)),
+fn is_valid_identifier(value: &str) -> bool {
+  !value.is_empty() && value.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+fn parse_pre_release(pre_release: &str) -> Option<Vec<PreReleaseIdentifier>> {
+  pre_release
+    .split('.')
+    .map(|id| {
+      if !is_valid_identifier(id) {
+        None
+      } else if id.chars().all(|c| c.is_ascii_digit()) {
+        if has_leading_zero(id) {
+          None
+        } else {
+          parse_numeric_identifier(id).map(PreReleaseIdentifier::Numeric)
+        }
+      } else {
+        Some(PreReleaseIdentifier::AlphaNumeric(id.to_owned()))
       }
+    })
+    .collect()
+}
+
+fn parse_build(build: &str) -> Option<Vec<String>> {
+  build
+    .split('.')
+    .map(|id| is_valid_identifier(id).then(|| id.to_owned()))
+    .collect()
+}
+
+/// Parses and validates a version string such as "1.2.3-rc.1+build.5".
+impl FromStr for ValidatedPackage {
+    type Err = WaffleError;
+
+    fn from_str(version: &str) -> Result<Self, Self::Err> {
+      Package { name: None, version: version.to_owned() }.try_into()
     }
 }
 
 impl From<ValidatedPackage> for Value {
   fn from(package: ValidatedPackage) -> Self {
-    let version = s!("{}.{}.{}", package.major, package.minor, package.patch);
-    Value::String(Formatted::new(version))
+    Value::String(Formatted::new(package.to_string()))
   }
 }
 
 #[cfg(test)]
 mod tests {
 
-    // TODO: Try out a fuzzer
+    mod cargotoml {
+      use super::super::{CargoToml, VersionSource};
+      use pretty_assertions::assert_eq;
+
+      #[test]
+      fn package_version_source() {
+        assert_version_source("[package]\nversion = \"1.2.3\"", Some("package: 1.2.3"));
+      }
+
+      #[test]
+      fn workspace_package_version_source() {
+        assert_version_source("[workspace]\n[workspace.package]\nversion = \"1.2.3\"", Some("workspace: 1.2.3"));
+      }
+
+      #[test]
+      fn inherited_version_source() {
+        assert_version_source("[package]\nversion.workspace = true", Some("inherited"));
+      }
+
+      #[test]
+      fn inherited_from_own_workspace_version_source() {
+        assert_version_source("[package]\nversion.workspace = true\n[workspace.package]\nversion = \"1.2.3\"", Some("workspace: 1.2.3"));
+      }
+
+      #[test]
+      fn package_config_overrides_workspace_config() {
+        let content = "[package.metadata.waffle]\ntag-template = \"{name}-v{version}\"\n[workspace.metadata.waffle]\ntag-template = \"v{version}\"";
+        let cargo_toml: CargoToml = toml::from_str(content).unwrap();
+        let config = cargo_toml.package_config().or(cargo_toml.workspace_config());
+
+        assert_eq!(config.tag_template.as_deref(), Some("{name}-v{version}"))
+      }
+
+      #[test]
+      fn missing_version_source() {
+        assert_version_source("[package]\nname = \"waffle\"", None);
+        assert_version_source("[workspace]\nmembers = []", None);
+      }
+
+      fn assert_version_source(content: &str, expected: Option<&str>) {
+        let cargo_toml: CargoToml = toml::from_str(content).unwrap();
+        let source = match cargo_toml.version_source() {
+          VersionSource::Package(version) => Some(format!("package: {version}")),
+          VersionSource::WorkspacePackage(version) => Some(format!("workspace: {version}")),
+          VersionSource::Inherited => Some("inherited".to_owned()),
+          VersionSource::NotFound => None,
+        };
+
+        assert_eq!(source.as_deref(), expected)
+      }
+    }
+
     mod validatedpackage {
 
       mod bump {
         use super::super::super::ValidatedPackage;
-        use crate::args::BumpType;
+        use crate::args::{BumpType, PreReleaseChannel};
+        use crate::error::WaffleError;
         use pretty_assertions::assert_eq;
 
         #[test]
@@ -152,9 +524,120 @@ mod tests {
           assert_bump_version("1.2.3", BumpType::Major, "2.0.0");
         }
 
+        #[test]
+        fn pre_release_version() {
+          assert_bump_version("1.2.3-rc.1+build.5", BumpType::Patch, "1.2.4");
+          assert_bump_version("1.2.3-rc.1", BumpType::Minor, "1.3.0");
+          assert_bump_version("1.2.3-alpha", BumpType::Major, "2.0.0");
+        }
+
+        #[test]
+        fn pre_release_from_release_version() {
+          assert_bump_version("1.2.3", BumpType::PreRelease(PreReleaseChannel::Rc), "1.2.4-rc.1");
+          assert_bump_version("1.2.3", BumpType::PrePatch(PreReleaseChannel::Beta), "1.2.4-beta.1");
+          assert_bump_version("1.2.3", BumpType::PreMinor(PreReleaseChannel::Alpha), "1.3.0-alpha.1");
+          assert_bump_version("1.2.3", BumpType::PreMajor(PreReleaseChannel::Rc), "2.0.0-rc.1");
+        }
+
+        #[test]
+        fn pre_release_increment() {
+          assert_bump_version("1.2.4-rc.1", BumpType::PreRelease(PreReleaseChannel::Rc), "1.2.4-rc.2");
+          assert_bump_version("1.2.4-rc.9+build.5", BumpType::PreRelease(PreReleaseChannel::Rc), "1.2.4-rc.10");
+        }
+
+        #[test]
+        fn pre_release_channel_switch() {
+          assert_bump_version("1.2.4-beta.3", BumpType::PreRelease(PreReleaseChannel::Rc), "1.2.4-rc.1");
+          assert_bump_version("1.2.4-alpha", BumpType::PreRelease(PreReleaseChannel::Alpha), "1.2.4-alpha.1");
+          assert_bump_version("1.2.4-0", BumpType::PreRelease(PreReleaseChannel::Alpha), "1.2.4-alpha.1");
+        }
+
+        #[test]
+        fn pre_release_channel_downgrade() {
+          let package = ValidatedPackage::new("1.2.4-rc.2");
+          let bumped_package = package.bump_version(BumpType::PreRelease(PreReleaseChannel::Beta));
+
+          assert_eq!(bumped_package, Err(WaffleError::PreReleaseChannelDowngrade(package, PreReleaseChannel::Beta)))
+        }
+
+        #[test]
+        fn release_pre_release_version() {
+          assert_bump_version("2.0.0-rc.4", BumpType::Release, "2.0.0");
+          assert_bump_version("2.0.0-rc.4+build.5", BumpType::Release, "2.0.0");
+        }
+
+        #[test]
+        fn release_release_version() {
+          let package = ValidatedPackage::new("2.0.0+build.5");
+          let bumped_package = package.bump_version(BumpType::Release);
+
+          assert_eq!(bumped_package, Err(WaffleError::AlreadyReleased(package)))
+        }
+
+        #[test]
+        fn base_bump_of_pre_release() {
+          assert_bump_version("1.3.0-alpha.2", BumpType::PreMinor(PreReleaseChannel::Alpha), "1.4.0-alpha.1");
+        }
+
+        #[test]
+        fn components_beyond_u16() {
+          assert_bump_version("0.1.70000", BumpType::Patch, "0.1.70001");
+          assert_bump_version("2024.1018.0", BumpType::Minor, "2024.1019.0");
+          assert_bump_version("18446744073709551614.0.0", BumpType::Major, "18446744073709551615.0.0");
+        }
+
+        #[test]
+        fn component_overflow() {
+          let max = u64::MAX;
+          let cases = [
+            (format!("{max}.0.0"), BumpType::Major),
+            (format!("1.{max}.0"), BumpType::Minor),
+            (format!("1.2.{max}"), BumpType::Patch),
+            (format!("{max}.0.0"), BumpType::PreMajor(PreReleaseChannel::Beta)),
+            (format!("1.{max}.0"), BumpType::PreMinor(PreReleaseChannel::Beta)),
+            (format!("1.2.{max}"), BumpType::PrePatch(PreReleaseChannel::Beta)),
+            (format!("1.2.{max}"), BumpType::PreRelease(PreReleaseChannel::Beta)),
+            (format!("1.2.3-beta.{max}"), BumpType::PreRelease(PreReleaseChannel::Beta)),
+          ];
+
+          for (version, bump_type) in cases {
+            let package = ValidatedPackage::new(&version);
+            let expected_bump_type = match bump_type {
+              // A release version gets a patch bump before starting a pre-release
+              BumpType::PreRelease(channel) if !package.is_pre_release() => BumpType::PrePatch(channel),
+              _ => bump_type.clone(),
+            };
+
+            assert_eq!(package.bump_version(bump_type), Err(WaffleError::VersionOverflow(package, expected_bump_type)))
+          }
+        }
+
+        #[test]
+        fn boundary_bumps_never_panic() {
+          let components = [0, 1, u64::from(u16::MAX), u64::from(u16::MAX) + 1, u64::MAX - 1, u64::MAX];
+
+          for major in components {
+            for minor in components {
+              for patch in components {
+                for suffix in ["", "-alpha.1", &format!("-rc.{}", u64::MAX)] {
+                  let package = ValidatedPackage::new(&format!("{major}.{minor}.{patch}{suffix}"));
+
+                  for bump_type in BumpType::all() {
+                    match package.bump_version(bump_type) {
+                      Ok(next_version) => assert!(next_version > package, "{next_version} is not greater than {package}"),
+                      Err(WaffleError::VersionOverflow(..) | WaffleError::PreReleaseChannelDowngrade(..) | WaffleError::AlreadyReleased(_)) => (),
+                      Err(error) => panic!("Unexpected error bumping {package}: {error:?}"),
+                    }
+                  }
+                }
+              }
+            }
+          }
+        }
+
         fn assert_bump_version(version: &str, bump_type: BumpType, expected_version: &str) {
           let package = ValidatedPackage::new(version);
-          let bumped_package = package.bump_version(bump_type);
+          let bumped_package = package.bump_version(bump_type).unwrap();
           let expected_bumped_package = ValidatedPackage::new(expected_version);
 
           assert_eq!(bumped_package, expected_bumped_package)
@@ -164,7 +647,7 @@ mod tests {
       mod try_from {
         use crate::error::{ResultW, WaffleError};
 
-        use super::super::super::{Package, ValidatedPackage};
+        use super::super::super::{Package, PreReleaseIdentifier, ValidatedPackage};
         use pretty_assertions::assert_eq;
 
         #[test]
@@ -196,6 +679,124 @@ mod tests {
 
           assert_eq!(validated_package_result, expected_package_error)
         }
+
+        #[test]
+        fn pre_release_and_build_package_conversion() {
+          let validated_package: ValidatedPackage = Package::new("1.2.3-rc.1+build.5").try_into().unwrap();
+          let expected_package =
+            ValidatedPackage {
+              major: 1,
+              minor: 2,
+              patch: 3,
+              pre_release: vec![PreReleaseIdentifier::AlphaNumeric("rc".to_owned()), PreReleaseIdentifier::Numeric(1)],
+              build: vec!["build".to_owned(), "5".to_owned()],
+            };
+
+          assert_eq!(validated_package, expected_package)
+        }
+
+        #[test]
+        fn display_round_trips() {
+          let versions = ["1.2.3", "1.2.3-rc.1", "1.2.3+build.5", "1.0.0-x-y.7.z.92+exp.sha.5114f85", "1.0.0+0.build.1-rc.10000aaa-kk-0.1"];
+
+          for version in versions {
+            assert_eq!(ValidatedPackage::new(version).to_string(), version)
+          }
+        }
+
+        #[test]
+        fn leading_zero_package_conversion() {
+          assert_package_error("01.2.3", WaffleError::LeadingZeroInVersion);
+          assert_package_error("1.02.3", WaffleError::LeadingZeroInVersion);
+          assert_package_error("1.2.03", WaffleError::LeadingZeroInVersion);
+          assert_package_error("1.2.3-rc.01", WaffleError::InvalidPreRelease);
+        }
+
+        #[test]
+        fn leading_zero_allowed_in_alphanumeric_and_build_identifiers() {
+          let validated_package_result: ResultW<ValidatedPackage> = Package::new("1.2.3-0a.01b+001").try_into();
+
+          assert!(validated_package_result.is_ok())
+        }
+
+        #[test]
+        fn invalid_pre_release_package_conversion() {
+          assert_package_error("1.2.3-", WaffleError::InvalidPreRelease);
+          assert_package_error("1.2.3-rc..1", WaffleError::InvalidPreRelease);
+          assert_package_error("1.2.3-rc.1_2", WaffleError::InvalidPreRelease);
+        }
+
+        #[test]
+        fn invalid_build_package_conversion() {
+          assert_package_error("1.2.3+", WaffleError::InvalidBuildMetadata);
+          assert_package_error("1.2.3+build..5", WaffleError::InvalidBuildMetadata);
+          assert_package_error("1.2.3-rc.1+build+5", WaffleError::InvalidBuildMetadata);
+        }
+
+        #[test]
+        fn large_package_conversion() {
+          let validated_package = ValidatedPackage::new(&format!("{}.1018.70000", u64::MAX));
+
+          assert_eq!((validated_package.major, validated_package.minor, validated_package.patch), (u64::MAX, 1018, 70000))
+        }
+
+        #[test]
+        fn too_large_package_conversion() {
+          assert_package_error("18446744073709551616.0.0", WaffleError::NonNumericVersions);
+        }
+
+        #[test]
+        fn signed_package_conversion() {
+          assert_package_error("+1.2.3", WaffleError::NonNumericVersions);
+          assert_package_error("1.+2.3", WaffleError::NonNumericVersions);
+        }
+
+        fn assert_package_error(version: &str, error: fn(Package) -> WaffleError) {
+          let package = Package::new(version);
+
+          let validated_package_result: ResultW<ValidatedPackage> = package.clone().try_into();
+          let expected_package_error = Err(error(package));
+
+          assert_eq!(validated_package_result, expected_package_error)
+        }
+      }
+
+      mod precedence {
+        use super::super::super::ValidatedPackage;
+        use std::cmp::Ordering;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn follows_semver_precedence() {
+          // Example from https://semver.org/#spec-item-11
+          let versions = [
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+            "1.0.1",
+            "1.1.0",
+            "2.0.0",
+          ];
+
+          for pair in versions.windows(2) {
+            let (lower, higher) = (ValidatedPackage::new(pair[0]), ValidatedPackage::new(pair[1]));
+            assert_eq!(lower.cmp_precedence(&higher), Ordering::Less, "{lower} < {higher}");
+            assert!(lower < higher, "{lower} < {higher}")
+          }
+        }
+
+        #[test]
+        fn ignores_build_metadata() {
+          let version = ValidatedPackage::new("1.0.0-rc.1+build.1");
+          let other_version = ValidatedPackage::new("1.0.0-rc.1+build.2");
+
+          assert_eq!(version.cmp_precedence(&other_version), Ordering::Equal)
+        }
       }
     }
 }
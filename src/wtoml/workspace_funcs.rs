@@ -0,0 +1,163 @@
+use std::path::{Path, PathBuf};
+use log::trace;
+
+use crate::error::{FileName, ResultW, WaffleError};
+use super::toml_funcs::{find_workspace_root, load_toml_file, parse_cargo_toml};
+
+
+/// Returns the workspace root toml file, which is either the supplied toml file or the nearest workspace root above it.
+pub fn get_workspace_toml_file(toml_file: &Path) -> ResultW<PathBuf> {
+  let content = load_toml_file(toml_file)?;
+
+  if parse_cargo_toml(toml_file, &content)?.workspace.is_some() {
+    toml_file
+      .canonicalize()
+      .map_err(|e| WaffleError::CouldNotReadTomlFile(FileName::new(toml_file), e.to_string()))
+  } else {
+    find_workspace_root(toml_file)?
+      .ok_or_else(|| WaffleError::NotAWorkspace(FileName::new(toml_file)))
+  }
+}
+
+
+/// Returns the toml files of all workspace members, expanding glob patterns in `members` and skipping any paths in
+/// `exclude`. The workspace root is included when it is also a package.
+pub fn get_workspace_members(workspace_toml_file: &Path) -> ResultW<Vec<PathBuf>> {
+  let content = load_toml_file(workspace_toml_file)?;
+  let cargo_toml = parse_cargo_toml(workspace_toml_file, &content)?;
+  let workspace_dir = workspace_toml_file.parent().unwrap_or(Path::new("."));
+
+  let (members, exclude) =
+    cargo_toml
+      .workspace
+      .map(|w| (w.members.unwrap_or_default(), w.exclude.unwrap_or_default()))
+      .unwrap_or_default();
+
+  let excluded_dirs: Vec<_> =
+    exclude
+      .iter()
+      .map(|dir| workspace_dir.join(dir))
+      .collect();
+
+  let mut member_toml_files = vec![];
+
+  if cargo_toml.package.is_some() {
+    member_toml_files.push(workspace_toml_file.to_owned())
+  }
+
+  for member in members {
+    let pattern = workspace_dir.join(&member);
+    let member_dirs =
+      glob::glob(&pattern.to_string_lossy())
+        .map_err(|e| WaffleError::InvalidWorkspaceMember(FileName::new(workspace_toml_file), e.to_string()))?;
+
+    for member_dir in member_dirs {
+      let member_dir = member_dir.map_err(|e| WaffleError::InvalidWorkspaceMember(FileName::new(workspace_toml_file), e.to_string()))?;
+      let member_toml_file = member_dir.join("Cargo.toml");
+
+      let is_excluded = excluded_dirs.iter().any(|excluded| member_dir.starts_with(excluded));
+      trace!("Workspace member {} matched by: {member}, excluded: {is_excluded}", member_dir.to_string_lossy());
+
+      if !is_excluded && member_toml_file.is_file() {
+        member_toml_files.push(member_toml_file)
+      }
+    }
+  }
+
+  member_toml_files.sort();
+  member_toml_files.dedup();
+
+  Ok(member_toml_files)
+}
+
+
+/// Returns the toml file of the workspace member with the supplied package name.
+pub fn get_workspace_member(workspace_toml_file: &Path, package_name: &str) -> ResultW<PathBuf> {
+  for member_toml_file in get_workspace_members(workspace_toml_file)? {
+    let content = load_toml_file(&member_toml_file)?;
+    if parse_cargo_toml(&member_toml_file, &content)?.package_name().as_deref() == Some(package_name) {
+      return Ok(member_toml_file)
+    }
+  }
+
+  Err(WaffleError::PackageNotFound(package_name.to_owned(), FileName::new(workspace_toml_file)))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+    use std::fs;
+
+    #[test]
+    fn expands_member_globs_and_excludes() {
+      let working_dir = tempdir().unwrap();
+      let root = working_dir.path().canonicalize().unwrap();
+      let workspace_toml_file = root.join("Cargo.toml");
+
+      fs::write(&workspace_toml_file, "[workspace]\nmembers = [\"app\", \"crates/*\"]\nexclude = [\"crates/ignored\"]\n").unwrap();
+
+      for member in ["app", "crates/one", "crates/two", "crates/ignored"] {
+        fs::create_dir_all(root.join(member)).unwrap();
+        fs::write(root.join(member).join("Cargo.toml"), "[package]\nversion = \"1.0.0\"\n").unwrap();
+      }
+
+      // Not a crate, as it has no Cargo.toml
+      fs::create_dir_all(root.join("crates/docs")).unwrap();
+
+      let members = get_workspace_members(&workspace_toml_file).unwrap();
+      let expected_members: Vec<_> =
+        ["app", "crates/one", "crates/two"]
+          .iter()
+          .map(|member| root.join(member).join("Cargo.toml"))
+          .collect();
+
+      assert_eq!(members, expected_members)
+    }
+
+    #[test]
+    fn includes_root_package() {
+      let working_dir = tempdir().unwrap();
+      let workspace_toml_file = working_dir.path().join("Cargo.toml");
+
+      fs::write(&workspace_toml_file, "[package]\nversion = \"1.0.0\"\n[workspace]\n").unwrap();
+
+      let members = get_workspace_members(&workspace_toml_file).unwrap();
+
+      assert_eq!(members, vec![workspace_toml_file])
+    }
+
+    #[test]
+    fn finds_workspace_toml_file_from_member() {
+      let working_dir = tempdir().unwrap();
+      let root = working_dir.path().canonicalize().unwrap();
+      let workspace_toml_file = root.join("Cargo.toml");
+      let member_toml_file = root.join("app").join("Cargo.toml");
+
+      fs::write(&workspace_toml_file, "[workspace]\nmembers = [\"app\"]\n").unwrap();
+      fs::create_dir_all(root.join("app")).unwrap();
+      fs::write(&member_toml_file, "[package]\nversion = \"1.0.0\"\n").unwrap();
+
+      assert_eq!(get_workspace_toml_file(&member_toml_file).unwrap(), workspace_toml_file);
+      assert_eq!(get_workspace_toml_file(&workspace_toml_file).unwrap(), workspace_toml_file)
+    }
+
+    #[test]
+    fn finds_workspace_member_by_package_name() {
+      let working_dir = tempdir().unwrap();
+      let root = working_dir.path();
+      let workspace_toml_file = root.join("Cargo.toml");
+
+      fs::write(&workspace_toml_file, "[workspace]\nmembers = [\"crates/*\"]\n").unwrap();
+
+      for member in ["one", "two"] {
+        fs::create_dir_all(root.join("crates").join(member)).unwrap();
+        fs::write(root.join("crates").join(member).join("Cargo.toml"), format!("[package]\nname = \"{member}\"\nversion = \"1.0.0\"\n")).unwrap();
+      }
+
+      assert_eq!(get_workspace_member(&workspace_toml_file, "two").unwrap(), root.join("crates/two/Cargo.toml"));
+      assert_eq!(get_workspace_member(&workspace_toml_file, "three"), Err(WaffleError::PackageNotFound("three".to_owned(), FileName::new(&workspace_toml_file))))
+    }
+}
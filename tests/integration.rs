@@ -26,7 +26,7 @@ fn get_current_package_version() {
   let working_dir = tempdir().unwrap();
   let (sample_toml_file, mut cmd) = setup_test(&working_dir);
 
-  let expected_version_string = [ComparisonType::Contains("1.2.3")];
+  let expected_info_string = [ComparisonType::Contains("Using toml file:")];
 
   cmd
     .arg("--toml-file")
@@ -34,70 +34,1356 @@ fn get_current_package_version() {
     .arg("get")
     .assert()
     .success()
+    .stdout("1.2.3\n")
+    .stderr(std_err_comparison(&expected_info_string));
+}
+
+
+#[test]
+fn get_current_package_version_quietly() {
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test(&working_dir);
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("get")
+    .arg("--quiet")
+    .assert()
+    .success()
+    .stdout("1.2.3\n")
+    .stderr("");
+}
+
+
+#[test]
+fn bump_current_package_version_quietly() {
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test(&working_dir);
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("-q")
+    .arg("bump")
+    .arg("-p")
+    .assert()
+    .success()
+    .stdout("Updated version from: 1.2.3 -> 1.2.4\n")
+    .stderr("");
+}
+
+
+#[test]
+fn tag_current_package_version() {
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test(&working_dir);
+
+  let expected_version_string = [ComparisonType::Contains("git tag v1.2.3")];
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("tag")
+    .assert()
+    .success()
+    .stdout(std_out_comparison(&expected_version_string));
+}
+
+
+#[test]
+fn bump_major_version() {
+  assert_version_bump("2.0.0", BumpType::Major)
+}
+
+
+#[test]
+fn bump_minor_version() {
+  assert_version_bump("1.3.0", BumpType::Minor)
+}
+
+
+#[test]
+fn bump_patch_version() {
+  assert_version_bump("1.2.4", BumpType::Patch)
+}
+
+
+#[test]
+fn bump_current_package_version_without_diff() {
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test(&working_dir);
+
+  let old_version_diff = s!("{}version = \"1.2.3\"", Colour::Red.paint("-"));
+  let new_version_diff = s!("{}version = \"2.0.0\"", Colour::Green.paint("+"));
+  let expected_version_string = "Updated version from: 1.2.3 -> 2.0.0";
+
+  let expected_info_comparisons =
+    [
+      ComparisonType::DoesNotContain(&old_version_diff),
+      ComparisonType::DoesNotContain(&new_version_diff),
+    ];
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("bump")
+    .arg("-M")
+    .arg("--no-diff")
+    .assert()
+    .success()
+    .stdout(s!("{expected_version_string}\n"))
+    .stderr(std_err_comparison(&expected_info_comparisons));
+}
+
+
+#[test]
+fn get_pre_release_package_version() {
+  let working_dir = tempdir().unwrap();
+  let content = SAMPLE_TOML_CONTENT.replace("1.2.3", "1.2.3-rc.1+build.5");
+  let (sample_toml_file, mut cmd) = setup_test_with_content(&working_dir, &content);
+
+  let expected_version_string = [ComparisonType::Contains("1.2.3-rc.1+build.5")];
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("get")
+    .assert()
+    .success()
+    .stdout(std_out_comparison(&expected_version_string));
+}
+
+
+#[test]
+fn bump_pre_release_package_version() {
+  let working_dir = tempdir().unwrap();
+  let content = SAMPLE_TOML_CONTENT.replace("1.2.3", "1.2.3-rc.1");
+  let (sample_toml_file, mut cmd) = setup_test_with_content(&working_dir, &content);
+
+  let expected_version_string = [ComparisonType::Contains("Updated version from: 1.2.3-rc.1 -> 1.3.0")];
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("bump")
+    .arg("-m")
+    .assert()
+    .success()
+    .stdout(std_out_comparison(&expected_version_string));
+
+  let updated_content = std::fs::read_to_string(&sample_toml_file).unwrap();
+  assert!(updated_content.contains(r#"version = "1.3.0""#))
+}
+
+
+#[test]
+fn bump_minor_pre_release_version() {
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test(&working_dir);
+
+  let expected_version_string = [ComparisonType::Contains("Updated version from: 1.2.3 -> 1.3.0-alpha.1")];
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("bump")
+    .arg("-m")
+    .arg("--pre")
+    .arg("alpha")
+    .assert()
+    .success()
+    .stdout(std_out_comparison(&expected_version_string));
+}
+
+
+#[test]
+fn release_pre_release_version() {
+  let working_dir = tempdir().unwrap();
+  let content = SAMPLE_TOML_CONTENT.replace("1.2.3", "2.0.0-rc.4");
+  let (sample_toml_file, mut cmd) = setup_test_with_content(&working_dir, &content);
+
+  let expected_version_string = [ComparisonType::Contains("Updated version from: 2.0.0-rc.4 -> 2.0.0")];
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("bump")
+    .arg("--release")
+    .assert()
+    .success()
+    .stdout(std_out_comparison(&expected_version_string));
+}
+
+
+#[test]
+fn release_release_version() {
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test(&working_dir);
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("bump")
+    .arg("--release")
+    .assert()
+    .code(6)
+    .stderr(predicate::str::contains("Version: 1.2.3 is already a release version"));
+
+  let content = std::fs::read_to_string(&sample_toml_file).unwrap();
+  assert_eq!(content, SAMPLE_TOML_CONTENT)
+}
+
+
+#[test]
+fn bump_large_version() {
+  let working_dir = tempdir().unwrap();
+  let content = SAMPLE_TOML_CONTENT.replace("1.2.3", "2024.1018.70000");
+  let (sample_toml_file, mut cmd) = setup_test_with_content(&working_dir, &content);
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("bump")
+    .arg("-p")
+    .assert()
+    .success()
+    .stdout("Updated version from: 2024.1018.70000 -> 2024.1018.70001\n");
+}
+
+
+#[test]
+fn bump_overflowing_version() {
+  let working_dir = tempdir().unwrap();
+  let content = SAMPLE_TOML_CONTENT.replace("1.2.3", "1.2.18446744073709551615");
+  let (sample_toml_file, mut cmd) = setup_test_with_content(&working_dir, &content);
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("bump")
+    .arg("-p")
+    .assert()
+    .code(6)
+    .stderr(predicate::str::contains("Could not apply a patch bump to version: 1.2.18446744073709551615, as it would overflow a version component."));
+
+  let content_after = std::fs::read_to_string(&sample_toml_file).unwrap();
+  assert_eq!(content_after, content)
+}
+
+
+#[test]
+fn set_package_version() {
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test(&working_dir);
+
+  let old_version_diff = s!("{}version = \"1.2.3\"", Colour::Red.paint("-"));
+  let new_version_diff = s!("{}version = \"3.0.0-beta.1\"", Colour::Green.paint("+"));
+  let expected_info_comparisons =
+    [
+      ComparisonType::Contains(&old_version_diff),
+      ComparisonType::Contains(&new_version_diff),
+    ];
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("set")
+    .arg("3.0.0-beta.1")
+    .assert()
+    .success()
+    .stdout("Updated version from: 1.2.3 -> 3.0.0-beta.1\n")
+    .stderr(std_err_comparison(&expected_info_comparisons));
+
+  let content = std::fs::read_to_string(&sample_toml_file).unwrap();
+  assert_eq!(content, SAMPLE_TOML_CONTENT.replace("1.2.3", "3.0.0-beta.1"))
+}
+
+
+#[test]
+fn set_lower_package_version() {
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test(&working_dir);
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("set")
+    .arg("1.2.3-rc.1")
+    .assert()
+    .code(6)
+    .stderr(predicate::str::contains("Version: 1.2.3-rc.1 is lower than the current version: 1.2.3"));
+
+  let content = std::fs::read_to_string(&sample_toml_file).unwrap();
+  assert_eq!(content, SAMPLE_TOML_CONTENT)
+}
+
+
+#[test]
+fn set_lower_package_version_with_allow_downgrade() {
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test(&working_dir);
+
+  let expected_comparisons = [ComparisonType::Contains("Updated version from: 1.2.3 -> 0.9.0")];
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("set")
+    .arg("0.9.0")
+    .arg("--allow-downgrade")
+    .assert()
+    .success()
+    .stdout(std_out_comparison(&expected_comparisons));
+}
+
+
+#[test]
+fn set_invalid_package_version() {
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test(&working_dir);
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("set")
+    .arg("1.2")
+    .assert()
+    .code(5)
+    .stderr(predicate::str::contains("is not a valid Semantic Version"));
+}
+
+
+#[test]
+fn get_workspace_package_version() {
+  let working_dir = tempdir().unwrap();
+  let (workspace_toml_file, _) = setup_workspace(&working_dir);
+
+  let expected_version_string = [ComparisonType::Contains("0.4.2")];
+
+  Command::cargo_bin("waffle").unwrap()
+    .arg("--toml-file")
+    .arg(&workspace_toml_file)
+    .arg("get")
+    .assert()
+    .success()
     .stdout(std_out_comparison(&expected_version_string));
 }
 
 
 #[test]
-fn tag_current_package_version() {
+fn get_inherited_member_package_version() {
+  let working_dir = tempdir().unwrap();
+  let (_, member_toml_file) = setup_workspace(&working_dir);
+
+  let expected_version_string = [ComparisonType::Contains("0.4.2")];
+
+  Command::cargo_bin("waffle").unwrap()
+    .arg("--toml-file")
+    .arg(&member_toml_file)
+    .arg("get")
+    .assert()
+    .success()
+    .stdout(std_out_comparison(&expected_version_string));
+}
+
+
+#[test]
+fn bump_inherited_member_package_version() {
+  let working_dir = tempdir().unwrap();
+  let (workspace_toml_file, member_toml_file) = setup_workspace(&working_dir);
+
+  let expected_comparisons = [ComparisonType::Contains("Updated version from: 0.4.2 -> 0.5.0")];
+
+  Command::cargo_bin("waffle").unwrap()
+    .arg("--toml-file")
+    .arg(&member_toml_file)
+    .arg("bump")
+    .arg("-m")
+    .assert()
+    .success()
+    .stdout(std_out_comparison(&expected_comparisons));
+
+  let workspace_content = std::fs::read_to_string(&workspace_toml_file).unwrap();
+  let member_content = std::fs::read_to_string(&member_toml_file).unwrap();
+
+  assert_eq!(workspace_content, WORKSPACE_TOML_CONTENT.replace("0.4.2", "0.5.0"));
+  assert_eq!(member_content, MEMBER_TOML_CONTENT)
+}
+
+
+#[test]
+fn bump_all_workspace_members() {
+  let working_dir = tempdir().unwrap();
+  let (workspace_toml_file, member_toml_file) = setup_workspace(&working_dir);
+  let other_member_toml_file = add_workspace_member(&working_dir, "crates/other", SAMPLE_TOML_CONTENT);
+  let excluded_member_toml_file = add_workspace_member(&working_dir, "crates/excluded", SAMPLE_TOML_CONTENT);
+
+  let expected_comparisons =
+    [
+      ComparisonType::Contains("Cargo.toml from: 0.4.2 -> 0.4.3"),
+      ComparisonType::Contains("Cargo.toml from: 1.2.3 -> 1.2.4"),
+    ];
+
+  Command::cargo_bin("waffle").unwrap()
+    .arg("--toml-file")
+    .arg(&member_toml_file)
+    .arg("bump")
+    .arg("-p")
+    .arg("--workspace")
+    .assert()
+    .success()
+    .stdout(std_out_comparison(&expected_comparisons));
+
+  assert_eq!(std::fs::read_to_string(&workspace_toml_file).unwrap(), WORKSPACE_TOML_CONTENT.replace("0.4.2", "0.4.3"));
+  assert_eq!(std::fs::read_to_string(&member_toml_file).unwrap(), MEMBER_TOML_CONTENT);
+  assert_eq!(std::fs::read_to_string(&other_member_toml_file).unwrap(), SAMPLE_TOML_CONTENT.replace("1.2.3", "1.2.4"));
+  assert_eq!(std::fs::read_to_string(&excluded_member_toml_file).unwrap(), SAMPLE_TOML_CONTENT)
+}
+
+
+#[test]
+fn bump_no_workspace_members_on_failure() {
+  let working_dir = tempdir().unwrap();
+  let (workspace_toml_file, _) = setup_workspace(&working_dir);
+  let other_member_toml_file = add_workspace_member(&working_dir, "crates/other", SAMPLE_TOML_CONTENT);
+  let invalid_content = SAMPLE_TOML_CONTENT.replace("1.2.3", "1.2");
+  add_workspace_member(&working_dir, "crates/invalid", &invalid_content);
+
+  Command::cargo_bin("waffle").unwrap()
+    .arg("--toml-file")
+    .arg(&workspace_toml_file)
+    .arg("bump")
+    .arg("-p")
+    .arg("--workspace")
+    .assert()
+    .code(5)
+    .stderr(predicate::str::contains("is not a valid Semantic Version"));
+
+  assert_eq!(std::fs::read_to_string(&workspace_toml_file).unwrap(), WORKSPACE_TOML_CONTENT);
+  assert_eq!(std::fs::read_to_string(&other_member_toml_file).unwrap(), SAMPLE_TOML_CONTENT)
+}
+
+
+#[test]
+fn bump_updates_workspace_dependency_requirements() {
+  let working_dir = tempdir().unwrap();
+  let (workspace_toml_file, _) = setup_workspace(&working_dir);
+  let dependency_toml_file = add_workspace_member(&working_dir, "crates/a", &SAMPLE_TOML_CONTENT.replace("Sample", "a"));
+  let dependent_toml_file = add_workspace_member(&working_dir, "crates/b", DEPENDENT_TOML_CONTENT);
+
+  Command::cargo_bin("waffle").unwrap()
+    .arg("--toml-file")
+    .arg(&dependency_toml_file)
+    .arg("bump")
+    .arg("-m")
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Updated version from: 1.2.3 -> 1.3.0"));
+
+  let expected_dependent_content =
+    DEPENDENT_TOML_CONTENT
+      .replace(r#"version = "1.2""#, r#"version = "1.3""#)
+      .replace(r#"version = "=1.2.3""#, r#"version = "=1.3.0""#);
+
+  assert_eq!(std::fs::read_to_string(&dependent_toml_file).unwrap(), expected_dependent_content);
+  assert_eq!(std::fs::read_to_string(&workspace_toml_file).unwrap(), WORKSPACE_TOML_CONTENT)
+}
+
+
+#[test]
+fn bump_updates_lock_file() {
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test(&working_dir);
+  let lock_file = working_dir.path().join("Cargo.lock");
+  std::fs::write(&lock_file, SAMPLE_LOCK_CONTENT).unwrap();
+
+  let old_version_diff = s!("{}version = \"1.2.3\"", Colour::Red.paint("-"));
+  let new_version_diff = s!("{}version = \"1.2.4\"", Colour::Green.paint("+"));
+  let expected_info_comparisons =
+    [
+      ComparisonType::Contains("Cargo.lock:"),
+      ComparisonType::Contains(&old_version_diff),
+      ComparisonType::Contains(&new_version_diff),
+    ];
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("bump")
+    .arg("-p")
+    .assert()
+    .success()
+    .stderr(std_err_comparison(&expected_info_comparisons));
+
+  let expected_lock_content = SAMPLE_LOCK_CONTENT.replace("name = \"Sample\"\nversion = \"1.2.3\"", "name = \"Sample\"\nversion = \"1.2.4\"");
+  assert_eq!(std::fs::read_to_string(&lock_file).unwrap(), expected_lock_content)
+}
+
+
+#[test]
+fn create_tag_for_current_package_version() {
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test(&working_dir);
+  setup_git_repository(&working_dir);
+  let head = git(&working_dir, &["rev-parse", "HEAD"]);
+
+  let expected_output = s!("Created tag v1.2.3 at commit {head}");
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("tag")
+    .arg("--create")
+    .arg("--annotated")
+    .arg("--message")
+    .arg("Sample {tag}")
+    .assert()
+    .success()
+    .stdout(predicate::str::contains(expected_output));
+
+  assert_eq!(git(&working_dir, &["tag", "--list", "--format=%(contents)", "v1.2.3"]), "Sample v1.2.3")
+}
+
+
+#[test]
+fn create_existing_tag() {
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test(&working_dir);
+  setup_git_repository(&working_dir);
+  git(&working_dir, &["tag", "v1.2.3"]);
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("tag")
+    .arg("--create")
+    .assert()
+    .code(7)
+    .stderr(predicate::str::contains("Git tag: v1.2.3 already exists"));
+}
+
+
+#[test]
+fn create_tag_with_dirty_working_tree() {
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test(&working_dir);
+  setup_git_repository(&working_dir);
+  std::fs::write(&sample_toml_file, SAMPLE_TOML_CONTENT.replace("1.2.3", "1.2.4")).unwrap();
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("tag")
+    .arg("--create")
+    .assert()
+    .code(7)
+    .stderr(predicate::str::contains("has uncommitted changes"));
+
+  assert_eq!(git(&working_dir, &["tag", "--list"]), "")
+}
+
+
+#[test]
+fn tag_current_package_version_with_tag_template() {
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test(&working_dir);
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("tag")
+    .arg("--tag-template")
+    .arg("{name}/{major}.{minor}.{patch}")
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("git tag Sample/1.2.3"));
+}
+
+
+#[test]
+fn tag_current_package_version_with_configured_tag_template() {
+  let working_dir = tempdir().unwrap();
+  let content = s!("{SAMPLE_TOML_CONTENT}\n[package.metadata.waffle]\ntag-template = \"{{name}}-v{{version}}\"\n");
+  let (sample_toml_file, mut cmd) = setup_test_with_content(&working_dir, &content);
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("tag")
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("git tag Sample-v1.2.3"));
+}
+
+
+#[test]
+fn bump_and_commit_changed_files() {
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test(&working_dir);
+  std::fs::write(working_dir.path().join("Cargo.lock"), SAMPLE_LOCK_CONTENT).unwrap();
+  std::fs::write(working_dir.path().join("other.txt"), "other").unwrap();
+  setup_git_repository(&working_dir);
+  std::fs::write(working_dir.path().join("other.txt"), "changed").unwrap();
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("bump")
+    .arg("-m")
+    .arg("--commit")
+    .arg("--commit-message")
+    .arg("Release {name} {old_version} -> {new_version}")
+    .arg("--tag")
+    .arg("--tag-template")
+    .arg("{name}-v{version}")
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Created tag Sample-v1.3.0 at commit"));
+
+  let head = git(&working_dir, &["rev-parse", "HEAD"]);
+
+  assert_eq!(git(&working_dir, &["show", "--name-only", "--format=%s", "HEAD"]), "Release Sample 1.2.3 -> 1.3.0\n\nCargo.lock\nSample.toml");
+  assert_eq!(git(&working_dir, &["rev-parse", "Sample-v1.3.0^{commit}"]), head);
+  assert_eq!(git(&working_dir, &["status", "--porcelain"]), "M other.txt")
+}
+
+
+#[test]
+fn bump_and_commit_with_existing_tag() {
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test(&working_dir);
+  setup_git_repository(&working_dir);
+  git(&working_dir, &["tag", "v1.2.4"]);
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("bump")
+    .arg("-p")
+    .arg("--commit")
+    .arg("--tag")
+    .assert()
+    .code(7)
+    .stderr(predicate::str::contains("Git tag: v1.2.4 already exists"));
+
+  assert_eq!(std::fs::read_to_string(&sample_toml_file).unwrap(), SAMPLE_TOML_CONTENT)
+}
+
+
+#[test]
+fn bump_and_commit_outside_git_repository() {
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test(&working_dir);
+
+  cmd
+    .env("GIT_CEILING_DIRECTORIES", working_dir.path())
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("bump")
+    .arg("-p")
+    .arg("--commit")
+    .assert()
+    .code(7)
+    .stderr(predicate::str::contains("is not in a Git repository"));
+
+  assert_eq!(std::fs::read_to_string(&sample_toml_file).unwrap(), SAMPLE_TOML_CONTENT)
+}
+
+
+#[test]
+fn bump_and_commit_with_ignored_lock_file() {
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test(&working_dir);
+  let lock_file = working_dir.path().join("Cargo.lock");
+  std::fs::write(&lock_file, SAMPLE_LOCK_CONTENT).unwrap();
+  std::fs::write(working_dir.path().join(".gitignore"), "Cargo.lock\n").unwrap();
+  setup_git_repository(&working_dir);
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("bump")
+    .arg("-p")
+    .arg("--commit")
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Committed 1 file(s) at commit"))
+    .stderr(predicate::str::contains("Not committing ignored file:"));
+
+  assert_eq!(git(&working_dir, &["show", "--name-only", "--format=%s", "HEAD"]), "Bump version to 1.2.4\n\nSample.toml");
+  assert_eq!(git(&working_dir, &["status", "--porcelain"]), "");
+  assert_ne!(std::fs::read_to_string(&lock_file).unwrap(), SAMPLE_LOCK_CONTENT)
+}
+
+
+#[cfg(unix)]
+#[test]
+fn bump_and_commit_restores_files_when_commit_fails() {
+  use std::os::unix::fs::PermissionsExt;
+
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test(&working_dir);
+  setup_git_repository(&working_dir);
+
+  let hook = working_dir.path().join(".git/hooks/pre-commit");
+  std::fs::write(&hook, "#!/bin/sh\nexit 1\n").unwrap();
+  std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("bump")
+    .arg("-p")
+    .arg("--commit")
+    .assert()
+    .code(7);
+
+  assert_eq!(std::fs::read_to_string(&sample_toml_file).unwrap(), SAMPLE_TOML_CONTENT);
+  assert_eq!(git(&working_dir, &["status", "--porcelain"]), "")
+}
+
+
+#[test]
+fn bump_with_bump_type_inferred_from_commits() {
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test(&working_dir);
+  setup_git_repository(&working_dir);
+  git(&working_dir, &["tag", "v1.2.3"]);
+  git(&working_dir, &["commit", "--quiet", "--allow-empty", "--message", "fix: a fix"]);
+  git(&working_dir, &["commit", "--quiet", "--allow-empty", "--message", "feat(cli): a feature"]);
+  git(&working_dir, &["commit", "--quiet", "--allow-empty", "--message", "chore: a chore"]);
+
+  let expected_info_comparisons =
+    [
+      ComparisonType::Contains("Inferred minor bump from commits since v1.2.3:"),
+      ComparisonType::Contains("feat(cli): a feature"),
+      ComparisonType::DoesNotContain("fix: a fix"),
+    ];
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("bump")
+    .arg("--auto")
+    .assert()
+    .success()
+    .stdout("Updated version from: 1.2.3 -> 1.3.0\n")
+    .stderr(std_err_comparison(&expected_info_comparisons));
+}
+
+
+#[test]
+fn bump_with_bump_type_inferred_from_commits_since_latest_version_tag() {
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test(&working_dir);
+  setup_git_repository(&working_dir);
+  git(&working_dir, &["tag", "v1.2.0"]);
+  git(&working_dir, &["commit", "--quiet", "--allow-empty", "--message", "feat!: a breaking change"]);
+  git(&working_dir, &["tag", "docs-published"]);
+  git(&working_dir, &["tag", "other-v1.2.3"]);
+  git(&working_dir, &["commit", "--quiet", "--allow-empty", "--message", "docs: update README"]);
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("bump")
+    .arg("--auto")
+    .assert()
+    .success()
+    .stdout("Updated version from: 1.2.3 -> 2.0.0\n")
+    .stderr(predicate::str::contains("Inferred major bump from commits since v1.2.0:"));
+}
+
+
+#[test]
+fn bump_with_bump_type_inferred_from_no_releasable_commits() {
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test(&working_dir);
+  setup_git_repository(&working_dir);
+  git(&working_dir, &["tag", "v1.2.3"]);
+  git(&working_dir, &["commit", "--quiet", "--allow-empty", "--message", "docs: update README"]);
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("bump")
+    .arg("--auto")
+    .assert()
+    .code(8)
+    .stderr(predicate::str::contains("no feat, fix or breaking change commits since tag: v1.2.3"));
+
+  assert_eq!(std::fs::read_to_string(&sample_toml_file).unwrap(), SAMPLE_TOML_CONTENT)
+}
+
+
+
+#[test]
+fn bump_and_generate_changelog_entry() {
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test(&working_dir);
+  setup_git_repository(&working_dir);
+  git(&working_dir, &["tag", "v1.2.3"]);
+  git(&working_dir, &["commit", "--quiet", "--allow-empty", "--message", "fix: a fix"]);
+  git(&working_dir, &["commit", "--quiet", "--allow-empty", "--message", "feat(cli): a feature"]);
+  git(&working_dir, &["commit", "--quiet", "--allow-empty", "--message", "chore: a chore"]);
+
+  let expected_info_comparisons = [ComparisonType::Contains("CHANGELOG.md:")];
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("bump")
+    .arg("--auto")
+    .arg("--changelog")
+    .assert()
+    .success()
+    .stdout("Updated version from: 1.2.3 -> 1.3.0\n")
+    .stderr(std_err_comparison(&expected_info_comparisons));
+
+  let changelog = std::fs::read_to_string(working_dir.path().join("CHANGELOG.md")).unwrap();
+
+  assert!(changelog.starts_with("# Changelog\n"));
+  assert!(changelog.contains("## [1.3.0] - "));
+  assert!(changelog.contains("### Features\n\n- a feature ("));
+  assert!(changelog.contains("### Fixes\n\n- a fix ("));
+  assert!(!changelog.contains("a chore"))
+}
+
+
+#[test]
+fn bump_releases_unreleased_changelog_section() {
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test(&working_dir);
+  let changelog_file = working_dir.path().join("CHANGELOG.md");
+  std::fs::write(&changelog_file, UNRELEASED_CHANGELOG_CONTENT).unwrap();
+
+  let expected_info_comparisons = [ComparisonType::Contains("CHANGELOG.md:")];
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("bump")
+    .arg("-m")
+    .assert()
+    .success()
+    .stdout("Updated version from: 1.2.3 -> 1.3.0\n")
+    .stderr(std_err_comparison(&expected_info_comparisons));
+
+  let changelog = std::fs::read_to_string(&changelog_file).unwrap();
+
+  assert!(changelog.contains("## [Unreleased]\n\n## [1.3.0] - "));
+  assert!(changelog.contains("[Unreleased]: https://github.com/ssanj/waffle/compare/v1.3.0...HEAD\n[1.3.0]: https://github.com/ssanj/waffle/compare/v1.2.3...v1.3.0\n"))
+}
+
+
+#[test]
+fn bump_with_empty_unreleased_changelog_section() {
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test(&working_dir);
+  let changelog_file = working_dir.path().join("CHANGELOG.md");
+  let changelog_content = "# Changelog\n\n## [Unreleased]\n\n## [1.2.3] - 2026-01-01\n\n- A set command\n";
+  std::fs::write(&changelog_file, changelog_content).unwrap();
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("bump")
+    .arg("-m")
+    .assert()
+    .code(8)
+    .stderr(predicate::str::contains("has nothing under its [Unreleased] section"));
+
+  assert_eq!(std::fs::read_to_string(&sample_toml_file).unwrap(), SAMPLE_TOML_CONTENT);
+  assert_eq!(std::fs::read_to_string(&changelog_file).unwrap(), changelog_content)
+}
+
+
+#[test]
+fn bump_with_empty_unreleased_changelog_section_allowed() {
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test(&working_dir);
+  let changelog_file = working_dir.path().join("CHANGELOG.md");
+  std::fs::write(&changelog_file, "# Changelog\n\n## [Unreleased]\n").unwrap();
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("bump")
+    .arg("-m")
+    .arg("--allow-empty-changelog")
+    .assert()
+    .success();
+
+  let changelog = std::fs::read_to_string(&changelog_file).unwrap();
+
+  assert!(changelog.contains("## [Unreleased]\n\n## [1.3.0] - "))
+}
+
+
+#[test]
+fn bump_without_bump_type() {
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test(&working_dir);
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("bump")
+    .assert()
+    .code(2)
+    .stderr(predicate::str::contains("At least one of Major, Minor, Patch or a pre-release channel is required"));
+}
+
+
+#[test]
+fn get_version_of_missing_toml_file() {
+  let working_dir = tempdir().unwrap();
+  let mut cmd = Command::cargo_bin("waffle").unwrap();
+
+  cmd
+    .arg("--toml-file")
+    .arg(working_dir.path().join("Cargo.toml"))
+    .arg("get")
+    .assert()
+    .code(3)
+    .stderr(predicate::str::contains("Could not read Toml file"));
+}
+
+
+#[test]
+fn get_version_without_package_version() {
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test_with_content(&working_dir, "[package]\nname = \"Sample\"\n");
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("get")
+    .assert()
+    .code(4)
+    .stderr(predicate::str::contains("has neither a package.version nor a workspace.package.version"));
+}
+
+
+#[test]
+fn bump_with_json_output() {
   let working_dir = tempdir().unwrap();
   let (sample_toml_file, mut cmd) = setup_test(&working_dir);
 
-  let expected_version_string = [ComparisonType::Contains("git tag v1.2.3")];
+  let assert =
+    cmd
+      .arg("--toml-file")
+      .arg(&sample_toml_file)
+      .arg("--output")
+      .arg("json")
+      .arg("bump")
+      .arg("-m")
+      .assert()
+      .success()
+      .stderr("");
+
+  let output: serde_json::Value = serde_json::from_slice(&assert.get_output().stdout).unwrap();
+  let canonical_toml_file = sample_toml_file.canonicalize().unwrap();
+
+  assert_eq!(output["output"], "bump");
+  assert_eq!(output["toml_file"], sample_toml_file.to_string_lossy().as_ref());
+  assert_eq!(output["name"], "Sample");
+  assert_eq!(output["old_version"], "1.2.3");
+  assert_eq!(output["new_version"], "1.3.0");
+  assert_eq!(output["files_changed"], serde_json::json!([canonical_toml_file]));
+  assert_eq!(output["commit"], serde_json::Value::Null);
+  assert_eq!(output["tag"], serde_json::Value::Null)
+}
+
+
+#[test]
+fn tag_with_json_output() {
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test(&working_dir);
+
+  let assert =
+    cmd
+      .arg("--toml-file")
+      .arg(&sample_toml_file)
+      .arg("tag")
+      .arg("--output")
+      .arg("json")
+      .assert()
+      .success();
+
+  let output: serde_json::Value = serde_json::from_slice(&assert.get_output().stdout).unwrap();
+
+  assert_eq!(output["output"], "tag");
+  assert_eq!(output["version"], "1.2.3");
+  assert_eq!(output["tag"], "v1.2.3")
+}
+
+
+#[test]
+fn error_with_json_output() {
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test(&working_dir);
+
+  let assert =
+    cmd
+      .arg("--toml-file")
+      .arg(&sample_toml_file)
+      .arg("--output")
+      .arg("json")
+      .arg("set")
+      .arg("1.0.0")
+      .assert()
+      .code(6);
+
+  let error: serde_json::Value = serde_json::from_slice(&assert.get_output().stderr).unwrap();
+
+  assert_eq!(error["error"]["exit_code"], 6);
+  assert_eq!(error["error"]["message"], "Version: 1.0.0 is lower than the current version: 1.2.3. Use --allow-downgrade to set it anyway.");
+  assert!(assert.get_output().stdout.is_empty())
+}
+
+
+#[test]
+fn bump_with_verbose_logging() {
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test(&working_dir);
+
+  let expected_log_comparisons =
+    [
+      ComparisonType::Contains("[DEBUG waffle::wtoml::toml_funcs] Updating package.version of"),
+      ComparisonType::Contains("[DEBUG waffle::changeset::changeset_tools] Replacing:"),
+      ComparisonType::DoesNotContain("[TRACE"),
+    ];
 
   cmd
     .arg("--toml-file")
     .arg(&sample_toml_file)
-    .arg("tag")
+    .arg("bump")
+    .arg("-p")
+    .arg("-v")
     .assert()
     .success()
-    .stdout(std_out_comparison(&expected_version_string));
+    .stdout("Updated version from: 1.2.3 -> 1.2.4\n")
+    .stderr(std_err_comparison(&expected_log_comparisons));
 }
 
 
 #[test]
-fn bump_major_version() {
-  assert_version_bump("2.0.0", BumpType::Major)
+fn bump_with_log_level() {
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test(&working_dir);
+
+  let expected_log_comparisons =
+    [
+      ComparisonType::Contains("[TRACE waffle::wtoml::toml_funcs] Parsed toml file"),
+      ComparisonType::Contains("[DEBUG waffle::workflow::workflow_tools] Bumping 1.2.3 with bump type: Patch"),
+    ];
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("--log-level")
+    .arg("trace")
+    .arg("bump")
+    .arg("-p")
+    .assert()
+    .success()
+    .stderr(std_err_comparison(&expected_log_comparisons));
 }
 
 
 #[test]
-fn bump_minor_version() {
-  assert_version_bump("1.3.0", BumpType::Minor)
+fn bump_with_dry_run() {
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test(&working_dir);
+  let lock_file = working_dir.path().join("Cargo.lock");
+  let changelog_file = working_dir.path().join("CHANGELOG.md");
+  std::fs::write(&lock_file, SAMPLE_LOCK_CONTENT).unwrap();
+  std::fs::write(&changelog_file, UNRELEASED_CHANGELOG_CONTENT).unwrap();
+  setup_git_repository(&working_dir);
+  let head = git(&working_dir, &["rev-parse", "HEAD"]);
+
+  let new_version_diff = s!("{}version = \"1.3.0\"", Colour::Green.paint("+"));
+  let expected_info_comparisons =
+    [
+      ComparisonType::Contains("Sample.toml:"),
+      ComparisonType::Contains("Cargo.lock:"),
+      ComparisonType::Contains("CHANGELOG.md:"),
+      ComparisonType::Contains(&new_version_diff),
+      ComparisonType::Contains("Would commit 3 file(s) with message: Bump version to 1.3.0"),
+      ComparisonType::Contains("Would create tag: v1.3.0"),
+    ];
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("bump")
+    .arg("-m")
+    .arg("--commit")
+    .arg("--tag")
+    .arg("--dry-run")
+    .assert()
+    .success()
+    .stdout("Updated version from: 1.2.3 -> 1.3.0\nDry run: no files were changed\n")
+    .stderr(std_err_comparison(&expected_info_comparisons));
+
+  assert_eq!(std::fs::read_to_string(&sample_toml_file).unwrap(), SAMPLE_TOML_CONTENT);
+  assert_eq!(std::fs::read_to_string(&lock_file).unwrap(), SAMPLE_LOCK_CONTENT);
+  assert_eq!(std::fs::read_to_string(&changelog_file).unwrap(), UNRELEASED_CHANGELOG_CONTENT);
+  assert_eq!(git(&working_dir, &["rev-parse", "HEAD"]), head);
+  assert_eq!(git(&working_dir, &["tag", "--list"]), "");
+  assert_eq!(std::fs::read_dir(working_dir.path()).unwrap().count(), 4)
 }
 
 
 #[test]
-fn bump_patch_version() {
-  assert_version_bump("1.2.4", BumpType::Patch)
+fn bump_all_workspace_members_with_dry_run() {
+  let working_dir = tempdir().unwrap();
+  let (workspace_toml_file, member_toml_file) = setup_workspace(&working_dir);
+  let mut cmd = Command::cargo_bin("waffle").unwrap();
+
+  let assert =
+    cmd
+      .arg("--toml-file")
+      .arg(&workspace_toml_file)
+      .arg("--output")
+      .arg("json")
+      .arg("bump")
+      .arg("-p")
+      .arg("--workspace")
+      .arg("--dry-run")
+      .assert()
+      .success();
+
+  let output: serde_json::Value = serde_json::from_slice(&assert.get_output().stdout).unwrap();
+
+  assert_eq!(output["output"], "workspace-bump");
+  assert_eq!(output["dry_run"], true);
+  assert_eq!(output["bumps"][0]["new_version"], "0.4.3");
+  assert_eq!(std::fs::read_to_string(&workspace_toml_file).unwrap(), WORKSPACE_TOML_CONTENT);
+  assert_eq!(std::fs::read_to_string(&member_toml_file).unwrap(), MEMBER_TOML_CONTENT)
 }
 
 
 #[test]
-fn bump_current_package_version_without_diff() {
+fn next_versions() {
   let working_dir = tempdir().unwrap();
   let (sample_toml_file, mut cmd) = setup_test(&working_dir);
 
-  let old_version_diff = s!("{}version = \"1.2.3\"", Colour::Red.paint("-"));
-  let new_version_diff = s!("{}version = \"2.0.0\"", Colour::Green.paint("+"));
-  let expected_version_string = "Updated version from: 1.2.3 -> 2.0.0";
-
   let expected_comparisons =
     [
-      ComparisonType::Contains(expected_version_string),
-      ComparisonType::DoesNotContain(&old_version_diff),
-      ComparisonType::DoesNotContain(&new_version_diff),
+      ComparisonType::Contains("current                  1.2.3\n"),
+      ComparisonType::Contains("major                    2.0.0\n"),
+      ComparisonType::Contains("minor                    1.3.0\n"),
+      ComparisonType::Contains("patch                    1.2.4\n"),
+      ComparisonType::Contains("minor alpha pre-release  1.3.0-alpha.1\n"),
+      ComparisonType::Contains("rc pre-release           1.2.4-rc.1\n"),
+      ComparisonType::DoesNotContain("\nrelease "),
     ];
 
   cmd
     .arg("--toml-file")
     .arg(&sample_toml_file)
-    .arg("bump")
-    .arg("-M")
-    .arg("--no-diff")
+    .arg("next")
     .assert()
     .success()
     .stdout(std_out_comparison(&expected_comparisons));
+
+  assert_eq!(std::fs::read_to_string(&sample_toml_file).unwrap(), SAMPLE_TOML_CONTENT)
+}
+
+
+#[test]
+fn next_version_of_single_bump_type() {
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test(&working_dir);
+
+  cmd
+    .arg("--toml-file")
+    .arg(&sample_toml_file)
+    .arg("next")
+    .arg("-m")
+    .arg("--pre")
+    .arg("beta")
+    .assert()
+    .success()
+    .stdout("1.3.0-beta.1\n");
+}
+
+
+#[test]
+fn next_versions_with_json_output() {
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test(&working_dir);
+
+  let assert =
+    cmd
+      .arg("--toml-file")
+      .arg(&sample_toml_file)
+      .arg("--output")
+      .arg("json")
+      .arg("next")
+      .assert()
+      .success();
+
+  let output: serde_json::Value = serde_json::from_slice(&assert.get_output().stdout).unwrap();
+
+  assert_eq!(output["output"], "next-versions");
+  assert_eq!(output["current_version"], "1.2.3");
+  assert_eq!(output["next_versions"][0], serde_json::json!({ "bump_type": "major", "next_version": "2.0.0" }));
+  assert_eq!(output["next_versions"][3], serde_json::json!({ "bump_type": "pre-major-alpha", "next_version": "2.0.0-alpha.1" }));
+  assert_eq!(output["next_versions"].as_array().unwrap().len(), 15)
+}
+
+
+#[test]
+fn next_version_of_single_bump_type_with_json_output() {
+  let working_dir = tempdir().unwrap();
+  let (sample_toml_file, mut cmd) = setup_test(&working_dir);
+
+  let assert =
+    cmd
+      .arg("--toml-file")
+      .arg(&sample_toml_file)
+      .arg("--output")
+      .arg("json")
+      .arg("next")
+      .arg("-M")
+      .arg("--pre")
+      .arg("alpha")
+      .assert()
+      .success()
+      .stderr("");
+
+  let output: serde_json::Value = serde_json::from_slice(&assert.get_output().stdout).unwrap();
+
+  assert_eq!(output["output"], "next-version");
+  assert_eq!(output["bump_type"], "pre-major-alpha");
+  assert_eq!(output["next_version"], "2.0.0-alpha.1")
+}
+
+
+#[test]
+fn get_version_of_nearest_package_toml_file() {
+  let working_dir = tempdir().unwrap();
+  setup_workspace(&working_dir);
+  let member_toml_file = add_workspace_member(&working_dir, "crates/one", "[package]\nname = \"one\"\nversion = \"1.0.0\"\n");
+  let src_dir = working_dir.path().join("crates").join("one").join("src");
+  std::fs::create_dir(&src_dir).unwrap();
+  let mut cmd = Command::cargo_bin("waffle").unwrap();
+
+  let expected_info_string = s!("Using toml file: {}", member_toml_file.to_string_lossy());
+  let expected_info_comparisons = [ComparisonType::Contains(&expected_info_string)];
+
+  cmd
+    .current_dir(&src_dir)
+    .arg("get")
+    .assert()
+    .success()
+    .stdout("1.0.0\n")
+    .stderr(std_err_comparison(&expected_info_comparisons));
+}
+
+
+#[test]
+fn get_version_of_workspace_root() {
+  let working_dir = tempdir().unwrap();
+  let (workspace_toml_file, _) = setup_workspace(&working_dir);
+  add_workspace_member(&working_dir, "crates/one", "[package]\nname = \"one\"\nversion = \"1.0.0\"\n");
+  let mut cmd = Command::cargo_bin("waffle").unwrap();
+
+  let expected_info_string = s!("Using toml file: {}", workspace_toml_file.canonicalize().unwrap().to_string_lossy());
+  let expected_info_comparisons = [ComparisonType::Contains(&expected_info_string)];
+
+  cmd
+    .current_dir(working_dir.path().join("crates").join("one"))
+    .arg("get")
+    .arg("--workspace-root")
+    .assert()
+    .success()
+    .stdout("0.4.2\n")
+    .stderr(std_err_comparison(&expected_info_comparisons));
+}
+
+
+#[test]
+fn get_version_without_toml_file() {
+  let working_dir = tempdir().unwrap();
+  let mut cmd = Command::cargo_bin("waffle").unwrap();
+
+  cmd
+    .current_dir(working_dir.path())
+    .arg("get")
+    .assert()
+    .code(3)
+    .stderr(predicate::str::contains("Could not find a Cargo.toml in:"));
+}
+
+
+#[test]
+fn bump_workspace_member_as_cargo_subcommand() {
+  let working_dir = tempdir().unwrap();
+  let (workspace_toml_file, _) = setup_workspace(&working_dir);
+  let member_toml_file = add_workspace_member(&working_dir, "crates/one", "[package]\nname = \"one\"\nversion = \"1.0.0\"\n");
+  let mut cmd = Command::cargo_bin("waffle").unwrap();
+
+  // Cargo passes the name of the subcommand as the first argument
+  cmd
+    .arg("waffle")
+    .arg("--manifest-path")
+    .arg(&workspace_toml_file)
+    .arg("-p")
+    .arg("one")
+    .arg("bump")
+    .arg("-p")
+    .assert()
+    .success()
+    .stdout("Updated version from: 1.0.0 -> 1.0.1\n");
+
+  assert_eq!(std::fs::read_to_string(&member_toml_file).unwrap(), "[package]\nname = \"one\"\nversion = \"1.0.1\"\n");
+  assert_eq!(std::fs::read_to_string(&workspace_toml_file).unwrap(), WORKSPACE_TOML_CONTENT)
+}
+
+
+#[test]
+fn bump_workspace_member_with_package_after_command() {
+  let working_dir = tempdir().unwrap();
+  let (workspace_toml_file, _) = setup_workspace(&working_dir);
+  let member_toml_file = add_workspace_member(&working_dir, "crates/one", "[package]\nname = \"one\"\nversion = \"1.0.0\"\n");
+  let mut cmd = Command::cargo_bin("cargo-waffle").unwrap();
+
+  cmd
+    .arg("waffle")
+    .arg("bump")
+    .arg("--manifest-path")
+    .arg(&workspace_toml_file)
+    .arg("--package")
+    .arg("one")
+    .arg("-m")
+    .assert()
+    .success()
+    .stdout("Updated version from: 1.0.0 -> 1.1.0\n");
+
+  assert_eq!(std::fs::read_to_string(&member_toml_file).unwrap(), "[package]\nname = \"one\"\nversion = \"1.1.0\"\n");
+}
+
+
+#[test]
+fn get_version_of_unknown_workspace_member() {
+  let working_dir = tempdir().unwrap();
+  let (workspace_toml_file, _) = setup_workspace(&working_dir);
+  let mut cmd = Command::cargo_bin("waffle").unwrap();
+
+  cmd
+    .arg("--manifest-path")
+    .arg(&workspace_toml_file)
+    .arg("--package")
+    .arg("unknown")
+    .arg("get")
+    .assert()
+    .code(4)
+    .stderr(predicate::str::contains("Package: unknown is not a member of the workspace at:"));
 }
 
 // ---------------------------------------------------------------------------------------------------------------------
@@ -124,6 +1410,74 @@ assert_cmd = "2"
 "#;
 
 
+const UNRELEASED_CHANGELOG_CONTENT: &str = r#"# Changelog
+
+## [Unreleased]
+
+### Added
+
+- A set command
+
+## [1.2.3] - 2026-01-01
+
+### Fixed
+
+- Empty versions
+
+[Unreleased]: https://github.com/ssanj/waffle/compare/v1.2.3...HEAD
+[1.2.3]: https://github.com/ssanj/waffle/compare/v1.2.2...v1.2.3
+"#;
+
+const SAMPLE_LOCK_CONTENT: &str = r#"# This file is automatically @generated by Cargo.
+# It is not intended for manual editing.
+version = 3
+
+[[package]]
+name = "Sample"
+version = "1.2.3"
+dependencies = [
+ "serde",
+]
+
+[[package]]
+name = "serde"
+version = "1.2.3"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#;
+
+
+const WORKSPACE_TOML_CONTENT: &str = r#"
+[workspace]
+members = ["member", "crates/*"]
+exclude = ["crates/excluded"]
+
+[workspace.package]
+version = "0.4.2"
+edition = "2021"
+"#;
+
+
+const MEMBER_TOML_CONTENT: &str = r#"
+[package]
+name = "member"
+version.workspace = true
+edition.workspace = true
+"#;
+
+
+const DEPENDENT_TOML_CONTENT: &str = r#"
+[package]
+name = "b"
+version = "0.1.0"
+
+[dependencies]
+a = { path = "../a", version = "1.2" }
+
+[dev-dependencies]
+a = { path = "../a", version = "=1.2.3" }
+"#;
+
+
 #[derive(Debug, Clone)]
 enum ComparisonType<'a> {
   Contains(&'a str),
@@ -145,19 +1499,27 @@ enum BumpType {
 }
 
 
+/// The result of a command is written to stdout.
 fn std_out_comparison<'a>(expected: &'a [ComparisonType<'a>]) -> FnPredicate<impl Fn(&[u8]) -> bool + 'a, [u8]> {
+  output_comparison("stdout", expected)
+}
+
+
+/// Informational messages, such as diffs, are written to stderr.
+fn std_err_comparison<'a>(expected: &'a [ComparisonType<'a>]) -> FnPredicate<impl Fn(&[u8]) -> bool + 'a, [u8]> {
+  output_comparison("stderr", expected)
+}
+
+
+fn output_comparison<'a>(stream: &'a str, expected: &'a [ComparisonType<'a>]) -> FnPredicate<impl Fn(&[u8]) -> bool + 'a, [u8]> {
     predicate::function(move |out: &[u8]| {
 
-    let expected_values: Vec<_> =
-      expected
-        .iter()
-        .cloned()
-        .collect();
+    let expected_values: Vec<_> = expected.to_vec();
 
-      let output = std::str::from_utf8(out).expect("Could not convert stdout to string");
+      let output = std::str::from_utf8(out).unwrap_or_else(|_| panic!("Could not convert {stream} to string"));
 
       expected_values.into_iter().all(|comparison| {
-        let error = s!("Could not validate stdout comparison: {}", comparison);
+        let error = s!("Could not validate {stream} comparison: {}", comparison);
         p!("{}", Colour::Red.paint(&error));
         p!("{}", "-".repeat(error.len()));
         match comparison {
@@ -169,28 +1531,83 @@ fn std_out_comparison<'a>(expected: &'a [ComparisonType<'a>]) -> FnPredicate<imp
 }
 
 
-fn setup_test<'a>(working_dir: &TempDir) -> (PathBuf, Command) {
+fn setup_test(working_dir: &TempDir) -> (PathBuf, Command) {
+  setup_test_with_content(working_dir, SAMPLE_TOML_CONTENT)
+}
+
+
+fn setup_test_with_content(working_dir: &TempDir, content: &str) -> (PathBuf, Command) {
   let sample_toml_file = working_dir.path().join("Sample.toml");
-  std::fs::write(&sample_toml_file, SAMPLE_TOML_CONTENT).unwrap();
+  std::fs::write(&sample_toml_file, content).unwrap();
   println!("{}", &sample_toml_file.as_path().to_string_lossy());
   let cmd = Command::cargo_bin("waffle").unwrap();
   (sample_toml_file, cmd)
 }
 
 
+/// Creates a virtual workspace with a single member that inherits its version.
+/// Returns the paths to the workspace and member toml files.
+fn setup_workspace(working_dir: &TempDir) -> (PathBuf, PathBuf) {
+  let workspace_toml_file = working_dir.path().join("Cargo.toml");
+  std::fs::write(&workspace_toml_file, WORKSPACE_TOML_CONTENT).unwrap();
+
+  let member_dir = working_dir.path().join("member");
+  std::fs::create_dir(&member_dir).unwrap();
+  let member_toml_file = member_dir.join("Cargo.toml");
+  std::fs::write(&member_toml_file, MEMBER_TOML_CONTENT).unwrap();
+
+  (workspace_toml_file, member_toml_file)
+}
+
+
+/// Adds a workspace member at the supplied path relative to the workspace root. Returns the path to its toml file.
+fn add_workspace_member(working_dir: &TempDir, member: &str, content: &str) -> PathBuf {
+  let member_dir = working_dir.path().join(member);
+  std::fs::create_dir_all(&member_dir).unwrap();
+  let member_toml_file = member_dir.join("Cargo.toml");
+  std::fs::write(&member_toml_file, content).unwrap();
+
+  member_toml_file
+}
+
+
+/// Runs git in the working directory, returning its trimmed stdout.
+fn git(working_dir: &TempDir, args: &[&str]) -> String {
+  let output =
+    std::process::Command::new("git")
+      .arg("-C")
+      .arg(working_dir.path())
+      .args(args)
+      .output()
+      .unwrap();
+
+  assert!(output.status.success(), "git {:?} failed: {}", args, String::from_utf8_lossy(&output.stderr));
+  String::from_utf8_lossy(&output.stdout).trim().to_owned()
+}
+
+
+/// Creates a Git repository in the working directory with all its files committed.
+fn setup_git_repository(working_dir: &TempDir) {
+  git(working_dir, &["init", "--quiet"]);
+  git(working_dir, &["config", "user.name", "waffle"]);
+  git(working_dir, &["config", "user.email", "waffle@example.com"]);
+  git(working_dir, &["add", "--all"]);
+  git(working_dir, &["commit", "--quiet", "--message", "initial"]);
+}
+
+
 fn assert_version_bump(new_version: &str, bump_type: BumpType) {
   let working_dir = tempdir().unwrap();
   let (sample_toml_file, mut cmd) = setup_test(&working_dir);
 
-  let expected_version_string =
+  let expected_info_string =
     [
-      s!("Updated version from: 1.2.3 -> {new_version}"),
       s!("{}version = \"1.2.3\"", Colour::Red.paint("-")),
       s!("{}version = \"{new_version}\"", Colour::Green.paint("+")),
     ];
 
-  let expected_comparisons: Vec<_> =
-    expected_version_string
+  let expected_info_comparisons: Vec<_> =
+    expected_info_string
       .iter()
       .map(|v| ComparisonType::Contains(v))
       .collect();
@@ -209,5 +1626,6 @@ fn assert_version_bump(new_version: &str, bump_type: BumpType) {
   cmd
     .assert()
     .success()
-    .stdout(std_out_comparison(&expected_comparisons));
+    .stdout(s!("Updated version from: 1.2.3 -> {new_version}\n"))
+    .stderr(std_err_comparison(&expected_info_comparisons));
 }
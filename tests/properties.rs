@@ -0,0 +1,253 @@
+use proptest::prelude::*;
+use std::cmp::Ordering;
+use std::format as s;
+use waffle::{BumpType, PreReleaseChannel, ValidatedPackage, WaffleError};
+
+proptest! {
+
+  #[test]
+  fn parse_display_round_trips(version in version_string()) {
+    let validated_version: ValidatedPackage = version.parse().unwrap();
+
+    prop_assert_eq!(validated_version.to_string(), version);
+  }
+
+
+  #[test]
+  fn parse_never_panics(version in "\\PC*") {
+    if let Ok(validated_version) = version.parse::<ValidatedPackage>() {
+      prop_assert_eq!(validated_version.to_string(), version);
+    }
+  }
+
+
+  #[test]
+  fn bumps_are_strictly_greater(version in version_string(), bump_type in bump_type()) {
+    let current_version: ValidatedPackage = version.parse().unwrap();
+
+    match current_version.bump_version(bump_type) {
+      Ok(next_version) => prop_assert_eq!(next_version.cmp_precedence(&current_version), Ordering::Greater),
+      Err(WaffleError::AlreadyReleased(_) | WaffleError::PreReleaseChannelDowngrade(..) | WaffleError::VersionOverflow(..)) => (),
+      Err(error) => prop_assert!(false, "Unexpected error bumping {}: {:?}", current_version, error),
+    }
+  }
+
+
+  #[test]
+  fn bumps_reset_lower_components(version in version_string()) {
+    let current = version.parse::<ValidatedPackage>().unwrap();
+    let (major, minor, patch) = (current.major, current.minor, current.patch);
+
+    if let Ok(next) = current.bump_version(BumpType::Major) {
+      prop_assert_eq!((next.major, next.minor, next.patch), (major + 1, 0, 0));
+      prop_assert!(!next.is_pre_release() && next.build.is_empty());
+    }
+
+    if let Ok(next) = current.bump_version(BumpType::Minor) {
+      prop_assert_eq!((next.major, next.minor, next.patch), (major, minor + 1, 0));
+      prop_assert!(!next.is_pre_release() && next.build.is_empty());
+    }
+
+    if let Ok(next) = current.bump_version(BumpType::Patch) {
+      prop_assert_eq!((next.major, next.minor, next.patch), (major, minor, patch + 1));
+      prop_assert!(!next.is_pre_release() && next.build.is_empty());
+    }
+
+    if let Ok(next) = current.bump_version(BumpType::Release) {
+      prop_assert_eq!((next.major, next.minor, next.patch), (major, minor, patch));
+      prop_assert!(!next.is_pre_release() && next.build.is_empty());
+    }
+  }
+
+
+  #[test]
+  fn pre_release_bumps_start_at_one(version in version_string(), channel in channel()) {
+    let current = version.parse::<ValidatedPackage>().unwrap();
+    let expected_pre_release = s!("-{channel}.1");
+
+    if let Ok(next) = current.bump_version(BumpType::PreMajor(channel)) {
+      prop_assert_eq!(next.to_string(), s!("{}.0.0{expected_pre_release}", current.major + 1));
+    }
+
+    if let Ok(next) = current.bump_version(BumpType::PreMinor(channel)) {
+      prop_assert_eq!(next.to_string(), s!("{}.{}.0{expected_pre_release}", current.major, current.minor + 1));
+    }
+
+    if let Ok(next) = current.bump_version(BumpType::PrePatch(channel)) {
+      prop_assert_eq!(next.to_string(), s!("{}.{}.{}{expected_pre_release}", current.major, current.minor, current.patch + 1));
+    }
+  }
+
+
+  #[test]
+  fn update_toml_only_changes_the_version_line(manifest in manifest(), next_version in version_string()) {
+    let (content, version_line, decorated_version_line) = manifest;
+    let next_version: ValidatedPackage = next_version.parse().unwrap();
+
+    let updated_content = waffle::update_toml("Cargo.toml", &content, next_version.clone()).unwrap().to_string();
+
+    let lines: Vec<&str> = content.lines().collect();
+    let updated_lines: Vec<&str> = updated_content.lines().collect();
+    prop_assert_eq!(updated_lines.len(), lines.len());
+
+    for (index, (line, updated_line)) in lines.iter().zip(updated_lines.iter()).enumerate() {
+      if index == version_line {
+        prop_assert_eq!(updated_line.to_string(), decorated_version_line.render(&next_version.to_string()));
+      } else {
+        prop_assert_eq!(updated_line, line);
+      }
+    }
+  }
+}
+
+
+/// Boundary values are picked more often, as they are the most likely to break.
+fn component() -> impl Strategy<Value = u64> {
+  prop_oneof![
+    0..1000u64,
+    Just(u64::from(u16::MAX)),
+    Just(u64::MAX - 1),
+    Just(u64::MAX),
+    any::<u64>(),
+  ]
+}
+
+
+fn pre_release_identifier() -> impl Strategy<Value = String> {
+  prop_oneof![
+    component().prop_map(|number| number.to_string()),
+    "[0-9A-Za-z-]{0,6}[A-Za-z-][0-9A-Za-z-]{0,6}",
+    Just("alpha".to_owned()),
+    Just("beta".to_owned()),
+    Just("rc".to_owned()),
+  ]
+}
+
+
+fn version_string() -> impl Strategy<Value = String> {
+  let pre_release = prop::collection::vec(pre_release_identifier(), 0..4);
+  let build = prop::collection::vec("[0-9A-Za-z-]{1,8}", 0..3);
+
+  (component(), component(), component(), pre_release, build)
+    .prop_map(|(major, minor, patch, pre_release, build)| {
+      let mut version = s!("{major}.{minor}.{patch}");
+
+      if !pre_release.is_empty() {
+        version.push_str(&s!("-{}", pre_release.join(".")));
+      }
+
+      if !build.is_empty() {
+        version.push_str(&s!("+{}", build.join(".")));
+      }
+
+      version
+    })
+}
+
+
+fn channel() -> impl Strategy<Value = PreReleaseChannel> {
+  prop_oneof![
+    Just(PreReleaseChannel::Alpha),
+    Just(PreReleaseChannel::Beta),
+    Just(PreReleaseChannel::Rc),
+  ]
+}
+
+
+fn bump_type() -> impl Strategy<Value = BumpType> {
+  prop::sample::select(BumpType::all())
+}
+
+
+fn toml_value() -> impl Strategy<Value = String> {
+  prop_oneof![
+    "[a-zA-Z0-9 ._-]{0,12}".prop_map(|value| s!("\"{value}\"")),
+    any::<i32>().prop_map(|value| value.to_string()),
+    any::<bool>().prop_map(|value| value.to_string()),
+    prop::collection::vec("[a-z]{1,6}", 0..3).prop_map(|values| s!("[{}]", values.iter().map(|v| s!("\"{v}\"")).collect::<Vec<_>>().join(", "))),
+  ]
+}
+
+
+/// Lines of unique key/value pairs, each optionally followed by a comment.
+fn key_values(excluded_keys: &'static [&'static str]) -> impl Strategy<Value = Vec<String>> {
+  prop::collection::btree_map("[a-z][a-z0-9_-]{0,10}", (toml_value(), prop::option::of("[a-zA-Z0-9 ]{0,12}")), 0..5)
+    .prop_map(move |entries| {
+      entries
+        .into_iter()
+        .filter(|(key, _)| !excluded_keys.contains(&key.as_str()))
+        .map(|(key, (value, comment))| {
+          match comment {
+            Some(comment) => s!("{key} = {value} # {comment}"),
+            None => s!("{key} = {value}"),
+          }
+        })
+        .collect()
+    })
+}
+
+
+/// A package.version line, with the text around the version string.
+#[derive(Debug, Clone)]
+struct VersionLine {
+  before_version: String,
+  after_version: String,
+}
+
+impl VersionLine {
+
+  fn render(&self, version: &str) -> String {
+    s!("{}\"{version}\"{}", self.before_version, self.after_version)
+  }
+}
+
+
+/// A version line with arbitrary spacing around the = and an optional trailing comment.
+fn version_line() -> impl Strategy<Value = VersionLine> {
+  let spacing = "[ \t]{0,3}";
+  let comment = prop::option::of(("[ \t]{0,3}", "[a-zA-Z0-9 ]{0,20}"));
+
+  (spacing, spacing, comment)
+    .prop_map(|(before_equals, after_equals, comment)| {
+      VersionLine {
+        before_version: s!("version{before_equals}={after_equals}"),
+        after_version: comment.map_or(String::new(), |(spacing, comment)| s!("{spacing}# {comment}")),
+      }
+    })
+}
+
+
+/// A manifest with arbitrary package keys around a decorated version and arbitrary dependencies.
+/// Returns the manifest, the index of its package.version line and that line.
+fn manifest() -> impl Strategy<Value = (String, usize, VersionLine)> {
+  let package_keys = ["name", "version"].as_slice();
+
+  (version_string(), version_line(), key_values(package_keys), key_values(package_keys), key_values(&[]), prop::option::of("[a-zA-Z0-9 ]{0,20}"))
+    .prop_map(|(version, decorated_version_line, before_version, after_version, dependencies, header_comment)| {
+      let mut lines = vec![];
+
+      if let Some(comment) = header_comment {
+        lines.push(s!("# {comment}"));
+      }
+
+      lines.push("[package]".to_owned());
+      lines.push("name = \"sample\"".to_owned());
+
+      // A key generated both before and after the version would be a duplicate, so only keep the first
+      lines.extend(before_version.iter().cloned());
+      let version_line = lines.len();
+      lines.push(decorated_version_line.render(&version));
+      lines.extend(after_version.into_iter().filter(|line| !before_version.iter().any(|before| key(before) == key(line))));
+
+      lines.push("".to_owned());
+      lines.push("[dependencies]".to_owned());
+      lines.extend(dependencies);
+
+      (s!("{}\n", lines.join("\n")), version_line, decorated_version_line)
+    })
+}
+
+
+fn key(line: &str) -> &str {
+  line.split_once(" = ").map(|(key, _)| key).unwrap_or(line)
+}